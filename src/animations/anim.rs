@@ -1,6 +1,107 @@
-use crate::core::{HasPoints, Mobject};
+use super::{
+    easing::{self, Easing},
+    Animatable,
+};
 
-pub struct Animation<T: HasPoints> {
-    mob: Mobject<T>,
-    target: Mobject<T>,
+/// Duration, start delay and easing curve of an animation.
+pub struct Timing {
+    pub duration: f32,
+    pub delay: f32,
+    pub easing: Box<dyn Easing>,
+}
+
+impl Timing {
+    pub fn new(duration: f32, easing: impl Easing + 'static) -> Self {
+        Self {
+            duration,
+            delay: 0.,
+            easing: Box::new(easing),
+        }
+    }
+
+    /// Eased progress at `time`, the start while delayed and the end once over.
+    pub fn progress(&self, time: f32) -> f32 {
+        let progress = match self.duration {
+            _ if time < self.delay => 0.,
+            0. => 1.,
+            duration => ((time - self.delay) / duration).clamp(0.0, 1.0),
+        };
+        self.easing.ease(progress)
+    }
+
+    /// Whether an animation applied at `time` still runs. Frames rarely land on the end, so
+    /// animations apply the progress first and stop after the first frame past it, which
+    /// leaves them in their final state.
+    pub fn is_running(&self, time: f32) -> bool {
+        time <= self.total()
+    }
+
+    pub fn total(&self) -> f32 {
+        self.delay + self.duration
+    }
+}
+
+/// Builder methods shared by every animation that owns a [`Timing`].
+pub trait Timed: Sized {
+    fn timing_mut(&mut self) -> &mut Timing;
+
+    fn with_easing(mut self, easing: impl Easing + 'static) -> Self {
+        self.timing_mut().easing = Box::new(easing);
+        self
+    }
+
+    fn with_duration(mut self, duration: f32) -> Self {
+        self.timing_mut().duration = duration.max(0.);
+        self
+    }
+
+    fn with_delay(mut self, delay: f32) -> Self {
+        self.timing_mut().delay = delay.max(0.);
+        self
+    }
+}
+
+/// Wraps any animation to change its timing by remapping the time it is applied at.
+///
+/// Animations without an end, like [`WaitUntil`](super::WaitUntil), can only be delayed.
+pub struct Animation<A: Animatable> {
+    anim: A,
+    timing: Timing,
+}
+
+impl<A: Animatable> Animation<A> {
+    pub fn new(anim: A) -> Self {
+        Self {
+            timing: Timing::new(anim.duration(), easing::Linear),
+            anim,
+        }
+    }
+}
+
+impl<A: Animatable> Timed for Animation<A> {
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
+impl<A: Animatable> Animatable for Animation<A> {
+    fn apply(&mut self, time: f32) -> bool {
+        if !self.anim.duration().is_finite() {
+            // Unbounded animations have no progress to ease, they get the time since the delay
+            // and run until they say they are done
+            let local = (time - self.timing.delay).max(0.);
+            return self.anim.apply(local) || time < self.timing.delay;
+        }
+        let progress = self.timing.progress(time);
+        self.anim.apply(progress * self.anim.duration());
+        self.timing.is_running(time)
+    }
+
+    fn begin(&mut self) {
+        self.anim.begin();
+    }
+
+    fn duration(&self) -> f32 {
+        self.timing.total()
+    }
 }
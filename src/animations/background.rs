@@ -0,0 +1,56 @@
+use std::{cell::RefCell, rc::Rc};
+
+use cgmath::{Vector4, VectorSpace};
+
+use crate::core::Background;
+
+use super::{easing, Animatable, Timed, Timing};
+
+/// Fades the background of a scene to a color, created by [`crate::core::Scene::animate_background`].
+///
+/// Image backgrounds have no color to fade from and are replaced when the animation starts.
+pub struct BackgroundTransition {
+    background: Rc<RefCell<Background>>,
+    initial: Vector4<f32>,
+    target: Vector4<f32>,
+    timing: Timing,
+}
+
+impl BackgroundTransition {
+    pub(crate) fn new(
+        background: Rc<RefCell<Background>>,
+        target: Vector4<f32>,
+        duration: f32,
+    ) -> Self {
+        Self {
+            background,
+            initial: target,
+            target,
+            timing: Timing::new(duration, easing::Smooth),
+        }
+    }
+}
+
+impl Timed for BackgroundTransition {
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
+impl Animatable for BackgroundTransition {
+    fn apply(&mut self, time: f32) -> bool {
+        let progress = self.timing.progress(time);
+        *self.background.borrow_mut() = Background::Color(self.initial.lerp(self.target, progress));
+        self.timing.is_running(time)
+    }
+
+    fn begin(&mut self) {
+        if let Background::Color(color) = *self.background.borrow() {
+            self.initial = color;
+        }
+    }
+
+    fn duration(&self) -> f32 {
+        self.timing.total()
+    }
+}
@@ -1,13 +1,19 @@
-use std::{cell::RefCell, ops::Deref, rc::Rc};
+use std::ops::Deref;
 
-use crate::core::{HasPoints, Mobject, Shape};
+use cgmath::{Quaternion, Vector3, Vector4};
 
-use super::Animatable;
+use crate::core::{Gradient, HasPoints, Mobject, Shape};
 
+use super::{easing, Animatable, Timed, Timing};
+
+/// Records changes on a copy of a mobject and animates the mobject towards them.
+///
+/// Created by [`Mobject::animate`], e.g. `scene.play(q.animate().shift((1., 0., 0.)).scale(2.))`.
 pub struct AnimationBuilder<T: HasPoints> {
     mob: Mobject<T>,
     target: Mobject<T>,
-    duration: f32,
+    initial: Option<Shape<T>>,
+    timing: Timing,
 }
 
 impl<T: HasPoints> Deref for AnimationBuilder<T> {
@@ -19,30 +25,91 @@ impl<T: HasPoints> Deref for AnimationBuilder<T> {
 }
 
 impl<T: HasPoints + Clone> AnimationBuilder<T> {
+    pub const DEFAULT_DURATION: f32 = 1.;
+
     pub fn new(mob: Mobject<T>, duration: f32) -> Self {
         Self {
             target: mob.clone(),
             mob,
-            duration,
+            initial: None,
+            timing: Timing::new(duration, easing::Smooth),
         }
     }
 }
 
-impl<T: HasPoints> Animatable for AnimationBuilder<T> {
-    fn apply(&self, time: f32) -> bool {
-        if time > self.duration {
-            return false;
-        }
-        let progress = (time / self.duration).clamp(0.0, 1.0);
+impl<T: HasPoints> AnimationBuilder<T> {
+    pub fn rotate(self, rotation: Quaternion<f32>) -> Self {
+        self.target.rotate(rotation);
+        self
+    }
+
+    pub fn scale_vec(self, scale: impl Into<Vector3<f32>>) -> Self {
+        self.target.scale_vec(scale);
+        self
+    }
+
+    pub fn scale(self, scale: f32) -> Self {
+        self.target.scale(scale);
+        self
+    }
+
+    pub fn shift(self, offset: impl Into<Vector3<f32>>) -> Self {
+        self.target.shift(offset);
+        self
+    }
+
+    pub fn color(self, color: impl Into<Vector4<f32>>) -> Self {
+        self.target.color(color);
+        self
+    }
+
+    pub fn gradient(self, gradient: Gradient) -> Self {
+        self.target.gradient(gradient);
+        self
+    }
+
+    pub fn stroke(self, width: f32, color: impl Into<Vector4<f32>>) -> Self {
+        self.target.stroke(width, color);
+        self
+    }
+
+    pub fn opacity(self, opacity: f32) -> Self {
+        self.target.opacity(opacity);
+        self
+    }
+}
+
+impl<T: HasPoints> Timed for AnimationBuilder<T> {
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
+impl<T: HasPoints + Clone> Animatable for AnimationBuilder<T> {
+    fn apply(&mut self, time: f32) -> bool {
+        let progress = self.timing.progress(time);
         self.mob.borrow_mut().interpolate(
             self.initial.as_ref().unwrap(),
-            self.target.as_ref().unwrap(),
-            self.easing.ease(progress),
+            &self.target.borrow(),
+            progress,
         );
-        true
+        self.timing.is_running(time)
     }
 
     fn begin(&mut self) {
-        todo!()
+        let mut initial = self.mob.borrow().clone();
+        if initial.points.is_empty() {
+            *initial.points = initial.calc_points();
+        }
+
+        let mut target = self.target.borrow_mut();
+        if target.points.is_empty() {
+            *target.points = target.calc_points();
+        }
+        self.initial = Some(initial);
+    }
+
+    fn duration(&self) -> f32 {
+        self.timing.total()
     }
 }
@@ -0,0 +1,180 @@
+use std::ops::Deref;
+
+use crate::core::{HasPoints, Mobject, Shape};
+
+use super::{easing, Animatable, Timed, Timing};
+
+/// Fades a mobject in from transparent to fully opaque, or to the opacity given to [`FadeIn::to`].
+pub struct FadeIn<T: HasPoints> {
+    mob: Mobject<T>,
+    /// Fill and stroke opacity reached at the end.
+    target: (f32, f32),
+    timing: Timing,
+}
+
+impl<T: HasPoints> FadeIn<T> {
+    pub fn new(mob: &Mobject<T>, duration: f32) -> Self {
+        Self {
+            mob: mob.ref_clone(),
+            target: (1., 1.),
+            timing: Timing::new(duration, easing::Smooth),
+        }
+    }
+
+    /// Sets the fill and stroke opacity reached at the end.
+    pub fn to(mut self, opacity: f32) -> Self {
+        self.target = (opacity, opacity);
+        self
+    }
+}
+
+impl<T: HasPoints> Timed for FadeIn<T> {
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
+impl<T: HasPoints> Animatable for FadeIn<T> {
+    fn apply(&mut self, time: f32) -> bool {
+        let progress = self.timing.progress(time);
+        let (fill, stroke) = self.target;
+        self.mob
+            .borrow_mut()
+            .set_opacity(fill * progress, stroke * progress);
+        self.timing.is_running(time)
+    }
+
+    fn begin(&mut self) {
+        // Hidden right away, so it doesn't show before its first frame when delayed or lagged
+        self.mob.borrow_mut().set_opacity(0., 0.);
+    }
+
+    fn duration(&self) -> f32 {
+        self.timing.total()
+    }
+}
+
+/// Fades a mobject out to transparent. It stays in the scene, remove it once done if it is
+/// not needed anymore.
+pub struct FadeOut<T: HasPoints> {
+    mob: Mobject<T>,
+    /// Fill and stroke opacity at the start.
+    initial: (f32, f32),
+    timing: Timing,
+}
+
+impl<T: HasPoints> FadeOut<T> {
+    pub fn new(mob: &Mobject<T>, duration: f32) -> Self {
+        Self {
+            mob: mob.ref_clone(),
+            initial: (1., 1.),
+            timing: Timing::new(duration, easing::Smooth),
+        }
+    }
+}
+
+impl<T: HasPoints> Timed for FadeOut<T> {
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
+impl<T: HasPoints> Animatable for FadeOut<T> {
+    fn apply(&mut self, time: f32) -> bool {
+        let progress = self.timing.progress(time);
+        let (fill, stroke) = self.initial;
+        self.mob
+            .borrow_mut()
+            .set_opacity(fill * (1. - progress), stroke * (1. - progress));
+        self.timing.is_running(time)
+    }
+
+    fn begin(&mut self) {
+        let shape = self.mob.borrow();
+        self.initial = (shape.fill_opacity(), shape.stroke_opacity());
+    }
+
+    fn duration(&self) -> f32 {
+        self.timing.total()
+    }
+}
+
+/// Cross-fades one mobject into another, for shapes too different to morph their points.
+///
+/// Both keep their own points and move together from the placement of `initial` to that of
+/// `target`, `initial` fading out while `target` fades in. `target` must be in the scene, and
+/// `initial` is left transparent in it.
+pub struct FadeTransform<T, V>
+where
+    T: HasPoints,
+    V: HasPoints,
+{
+    timing: Timing,
+    initial_mob: Mobject<T>,
+    target_mob: Mobject<V>,
+    initial: Option<Shape<T>>,
+    target: Option<Shape<V>>,
+}
+
+impl<T, V> FadeTransform<T, V>
+where
+    T: HasPoints,
+    V: HasPoints,
+{
+    pub fn new(initial: &Mobject<T>, target: &Mobject<V>, duration: f32) -> Self {
+        Self {
+            initial_mob: initial.ref_clone(),
+            target_mob: target.ref_clone(),
+            initial: None,
+            target: None,
+            timing: Timing::new(duration, easing::Smooth),
+        }
+    }
+}
+
+impl<T, V> Timed for FadeTransform<T, V>
+where
+    T: HasPoints,
+    V: HasPoints,
+{
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
+impl<T, V> Animatable for FadeTransform<T, V>
+where
+    T: HasPoints + Clone,
+    V: HasPoints + Clone,
+{
+    fn apply(&mut self, time: f32) -> bool {
+        let progress = self.timing.progress(time);
+        let (a, b) = (
+            self.initial.as_ref().unwrap(),
+            self.target.as_ref().unwrap(),
+        );
+
+        let mut initial = self.initial_mob.borrow_mut();
+        initial.interpolate_transform(a, b, progress);
+        initial.set_opacity(
+            a.fill_opacity() * (1. - progress),
+            a.stroke_opacity() * (1. - progress),
+        );
+
+        let mut target = self.target_mob.borrow_mut();
+        target.interpolate_transform(a, b, progress);
+        target.set_opacity(b.fill_opacity() * progress, b.stroke_opacity() * progress);
+        self.timing.is_running(time)
+    }
+
+    fn begin(&mut self) {
+        self.initial = Some(self.initial_mob.deref().borrow().clone());
+        self.target = Some(self.target_mob.deref().borrow().clone());
+        // The target starts out hidden on top of the initial mobject
+        self.apply(0.);
+    }
+
+    fn duration(&self) -> f32 {
+        self.timing.total()
+    }
+}
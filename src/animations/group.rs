@@ -0,0 +1,166 @@
+use super::Animatable;
+
+struct Child<'a> {
+    anim: Box<dyn Animatable + 'a>,
+    finished: bool,
+}
+
+/// Runs its animations on a shared timeline. Each child starts `lag_ratio` times the duration
+/// of the previous child after it, so `0.0` plays them all at once and `1.0` back to back.
+///
+/// All children are begun with the group, so each is in its start state until its start time
+/// is reached. Where children share mobjects, the earlier children's start states win.
+pub struct AnimationGroup<'a> {
+    children: Vec<Child<'a>>,
+    lag_ratio: f32,
+}
+
+/// Plays all animations at once, each with its own duration.
+pub type Parallel<'a> = AnimationGroup<'a>;
+
+impl<'a> AnimationGroup<'a> {
+    pub fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            lag_ratio: 0.,
+        }
+    }
+
+    pub fn with(mut self, anim: impl Animatable + 'a) -> Self {
+        self.children.push(Child {
+            anim: Box::new(anim),
+            finished: false,
+        });
+        self
+    }
+
+    pub fn lag_ratio(mut self, lag_ratio: f32) -> Self {
+        self.lag_ratio = lag_ratio;
+        self
+    }
+
+    fn start_times(&self) -> impl Iterator<Item = f32> + '_ {
+        self.children.iter().scan(0., |start, child| {
+            let current = *start;
+            *start += self.lag_ratio * child.anim.duration();
+            Some(current)
+        })
+    }
+}
+
+impl Default for AnimationGroup<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> FromIterator<Box<dyn Animatable + 'a>> for AnimationGroup<'a> {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Animatable + 'a>>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
+    }
+}
+
+impl Animatable for AnimationGroup<'_> {
+    fn apply(&mut self, time: f32) -> bool {
+        let starts = self.start_times().collect::<Vec<_>>();
+        let mut running = false;
+        for (child, start) in self.children.iter_mut().zip(starts) {
+            let duration = child.anim.duration();
+            running |= time <= start + duration;
+            if time < start || child.finished {
+                continue;
+            }
+            // Children that are passed get their final state exactly once
+            if time <= start + duration {
+                child.anim.apply(time - start);
+            } else {
+                child.anim.apply(duration);
+                child.finished = true;
+            }
+        }
+        running
+    }
+
+    fn begin(&mut self) {
+        // Backwards, so earlier children get the last word on the mobjects they share
+        for child in self.children.iter_mut().rev() {
+            child.anim.begin();
+            child.anim.apply(0.);
+            child.finished = false;
+        }
+    }
+
+    fn duration(&self) -> f32 {
+        self.start_times()
+            .zip(&self.children)
+            .map(|(start, child)| start + child.anim.duration())
+            .fold(0., f32::max)
+    }
+}
+
+macro_rules! define_group {
+    ($(#[$meta:meta])* $name:ident, $lag_ratio:expr) => {
+        $(#[$meta])*
+        pub struct $name<'a>(AnimationGroup<'a>);
+
+        impl<'a> $name<'a> {
+            pub fn with(self, anim: impl Animatable + 'a) -> Self {
+                Self(self.0.with(anim))
+            }
+        }
+
+        impl<'a> FromIterator<Box<dyn Animatable + 'a>> for $name<'a> {
+            fn from_iter<I: IntoIterator<Item = Box<dyn Animatable + 'a>>>(iter: I) -> Self {
+                Self(iter.into_iter().collect::<AnimationGroup>().lag_ratio($lag_ratio))
+            }
+        }
+
+        impl Animatable for $name<'_> {
+            fn apply(&mut self, time: f32) -> bool {
+                self.0.apply(time)
+            }
+
+            fn begin(&mut self) {
+                self.0.begin()
+            }
+
+            fn duration(&self) -> f32 {
+                self.0.duration()
+            }
+        }
+    };
+}
+
+define_group!(
+    /// Plays its animations back to back as a single animation.
+    Sequence,
+    1.
+);
+define_group!(
+    /// Starts each animation a fraction of the previous one's duration after it.
+    LaggedStart,
+    LaggedStart::DEFAULT_LAG_RATIO
+);
+
+/// Manim's name for [`Sequence`].
+pub type Succession<'a> = Sequence<'a>;
+
+impl Sequence<'_> {
+    pub fn new() -> Self {
+        Self(AnimationGroup::new().lag_ratio(1.))
+    }
+}
+
+impl Default for Sequence<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LaggedStart<'_> {
+    pub const DEFAULT_LAG_RATIO: f32 = 0.05;
+
+    pub fn new(lag_ratio: f32) -> Self {
+        Self(AnimationGroup::new().lag_ratio(lag_ratio))
+    }
+}
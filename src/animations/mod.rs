@@ -1,30 +1,52 @@
 pub mod anim;
+pub mod background;
 pub mod builder;
 pub mod easing;
-use std::{cell::RefCell, ops::Deref, rc::Rc};
-
-use easing::Easing;
+pub mod fade;
+pub mod group;
+pub mod wait;
+use std::ops::Deref;
 
 use crate::core::{HasPoints, Mobject, Shape};
+pub use anim::{Animation, Timed, Timing};
+pub use background::BackgroundTransition;
+pub use fade::{FadeIn, FadeOut, FadeTransform};
+pub use group::{AnimationGroup, LaggedStart, Parallel, Sequence, Succession};
+pub use wait::{Wait, WaitUntil};
 
 pub trait Animatable {
-    fn apply(&self, time: f32) -> bool;
+    fn apply(&mut self, time: f32) -> bool;
+    /// Called once when played, leaving the mobjects as they look at time 0.
     fn begin(&mut self);
+    fn duration(&self) -> f32;
     // fn get_target(&self) -> Rc<RefCell<dyn Renderable>>;
 }
 
+impl<A: Animatable + ?Sized> Animatable for Box<A> {
+    fn apply(&mut self, time: f32) -> bool {
+        (**self).apply(time)
+    }
+
+    fn begin(&mut self) {
+        (**self).begin()
+    }
+
+    fn duration(&self) -> f32 {
+        (**self).duration()
+    }
+}
+
 pub struct Transformation<T, V>
 where
     T: HasPoints,
     V: HasPoints,
 {
-    duration: f32,
+    timing: Timing,
     mob: Mobject<T>,
     initial: Option<Shape<T>>,
     target: Option<Shape<V>>,
     initial_mob: Mobject<T>,
     target_mob: Mobject<V>,
-    easing: Box<dyn Easing>,
 }
 
 impl<T, V> Transformation<T, V>
@@ -39,61 +61,59 @@ where
             mob: initial.ref_clone(),
             initial: None,
             target: None,
-            easing: Box::new(easing::Smooth),
-            duration,
+            timing: Timing::new(duration, easing::Smooth),
         }
     }
 }
 
+impl<T, V> Timed for Transformation<T, V>
+where
+    T: HasPoints,
+    V: HasPoints,
+{
+    fn timing_mut(&mut self) -> &mut Timing {
+        &mut self.timing
+    }
+}
+
 impl<T, V> Animatable for Transformation<T, V>
 where
     T: HasPoints + Clone,
     V: HasPoints + Clone,
 {
-    fn apply(&self, time: f32) -> bool {
-        if time > self.duration {
-            return false;
-        }
-        let progress = (time / self.duration).clamp(0.0, 1.0);
+    fn apply(&mut self, time: f32) -> bool {
+        let progress = self.timing.progress(time);
         self.mob.borrow_mut().interpolate(
             self.initial.as_ref().unwrap(),
             self.target.as_ref().unwrap(),
-            self.easing.ease(progress),
+            progress,
         );
-        true
+        self.timing.is_running(time)
     }
     // TODO: Clone points every time?
 
     fn begin(&mut self) {
         let mut initial = self.initial_mob.deref().borrow().clone();
         let mut target = self.target_mob.deref().borrow().clone();
-        if initial.points.len() == 0 {
+        if initial.points.is_empty() {
             *initial.points = initial.calc_points();
         }
 
-        if target.points.len() == 0 {
+        if target.points.is_empty() {
             *target.points = target.calc_points();
         }
 
-        let max_len = initial.points.len().max(target.points.len());
-
-        let len = initial.points.len();
-        if len < max_len {
-            *initial.points = (0..max_len)
-                .map(|i| initial.points[i * len / max_len])
-                .collect();
-        }
-
-        let len = target.points.len();
-        if len < max_len {
-            *target.points = (0..max_len)
-                .map(|i| target.points[i * len / max_len])
-                .collect();
-        }
+        let segments = initial.points.segments().max(target.points.segments());
+        *initial.points = initial.points.resample(segments);
+        *target.points = target.points.resample(segments);
         self.initial = Some(initial);
         self.target = Some(target);
     }
 
+    fn duration(&self) -> f32 {
+        self.timing.total()
+    }
+
     // fn get_target(&self) -> Rc<RefCell<dyn Renderable>> {
     //     self.mob
     // }
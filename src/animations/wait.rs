@@ -0,0 +1,49 @@
+use super::Animatable;
+
+/// Holds the current frame for a fixed amount of scene time.
+pub struct Wait {
+    duration: f32,
+}
+
+impl Wait {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration: duration.max(0.),
+        }
+    }
+}
+
+impl Animatable for Wait {
+    fn apply(&mut self, time: f32) -> bool {
+        time <= self.duration
+    }
+
+    fn begin(&mut self) {}
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+}
+
+/// Holds the current frame until the predicate returns true, checked once per frame.
+pub struct WaitUntil<F: FnMut() -> bool> {
+    predicate: F,
+}
+
+impl<F: FnMut() -> bool> WaitUntil<F> {
+    pub fn new(predicate: F) -> Self {
+        Self { predicate }
+    }
+}
+
+impl<F: FnMut() -> bool> Animatable for WaitUntil<F> {
+    fn apply(&mut self, _time: f32) -> bool {
+        !(self.predicate)()
+    }
+
+    fn begin(&mut self) {}
+
+    fn duration(&self) -> f32 {
+        f32::INFINITY
+    }
+}
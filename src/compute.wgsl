@@ -9,15 +9,29 @@ var<storage, read> points: array<vec3<f32>>;
 var<storage, read_write> vertices: array<Vertex>;
 @group(0) @binding(2)
 var<storage, read_write> indices: array<u32>;
+// First segment of the subpath each segment belongs to, or MOVE between subpaths
+@group(0) @binding(3)
+var<storage, read> pivots: array<u32>;
+
+const MOVE: u32 = 0xffffffffu;
 
 @compute @workgroup_size(64)
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     let i = id.x * 2u;
     let len = arrayLength(&points);
     if (i + 2u >= len) { return; }
-    let j = id.x * 3u;
+    let j = id.x * 4u;
     let k = id.x * 6u;
 
+    let pivot = pivots[id.x];
+    if (pivot == MOVE) {
+        for (var n = 0u; n < 6u; n++) {
+            indices[k + n] = j;
+        }
+        vertices[j] = Vertex(points[i], vec2<f32>(0.0, 0.0));
+        return;
+    }
+
     let v0 = points[i];
     let v1 = points[i + 1u];
     let v2 = points[i + 2u];
@@ -25,18 +39,14 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     vertices[j] = Vertex(v0, vec2<f32>(0.0, 0.0));
     vertices[j + 1] = Vertex(v1, vec2<f32>(0.5, 0.0));
     vertices[j + 2] = Vertex(v2, vec2<f32>(1.0, 1.0));
+    // The end again, with the uv of a point inside the curve for the fan triangle
+    vertices[j + 3] = Vertex(v2, vec2<f32>(0.0, 0.0));
 
     indices[k] = j;
     indices[k + 1] = j + 1;
     indices[k + 2] = j + 2;
-    indices[k + 3] = 0u;
+    // Fanning out from the start of the subpath closes it implicitly
+    indices[k + 3] = pivot * 4u;
     indices[k + 4] = j;
     indices[k + 5] = j + 3;
-    if (i == len - 3) {
-        if (points[0].x == points[len - 1].x && points[0].y == points[len - 1].y && points[0].z == points[len - 1].z) {
-            indices[k + 5] = 0u;
-        } else {
-            vertices[j + 3] = Vertex(v2, vec2<f32>(0.0, 0.0));
-        }
-    }
 }
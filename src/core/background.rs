@@ -0,0 +1,144 @@
+use std::rc::Rc;
+
+use cgmath::Vector4;
+
+use crate::texture::Texture;
+
+use super::{AnyContext, Attach, BindGroupBuilder, PipelineBuilder, RenderContext};
+
+/// What every frame is cleared to before the objects are drawn.
+#[derive(Clone)]
+pub enum Background {
+    /// A linear color. Transparent by default, so captured frames keep their alpha.
+    Color(Vector4<f32>),
+    /// An image stretched over the frame.
+    Image(Rc<image::DynamicImage>),
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Color(Vector4::new(0., 0., 0., 0.))
+    }
+}
+
+impl From<Vector4<f32>> for Background {
+    fn from(color: Vector4<f32>) -> Self {
+        Background::Color(color)
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Background {
+    fn from(color: (f32, f32, f32, f32)) -> Self {
+        Background::Color(color.into())
+    }
+}
+
+impl From<image::DynamicImage> for Background {
+    fn from(image: image::DynamicImage) -> Self {
+        Background::Image(Rc::new(image))
+    }
+}
+
+impl From<Rc<image::DynamicImage>> for Background {
+    fn from(image: Rc<image::DynamicImage>) -> Self {
+        Background::Image(image)
+    }
+}
+
+/// Clears the frame to the [`Background`], drawing images with a full-screen triangle.
+pub struct BackgroundRenderer {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    /// The image last drawn and its bind group, recreated when the image changes.
+    image: Option<(Rc<image::DynamicImage>, wgpu::BindGroup)>,
+}
+
+impl BackgroundRenderer {
+    pub fn new(ctx: &dyn RenderContext, sample_count: u32) -> Self {
+        let shader = ctx
+            .device()
+            .create_shader_module(wgpu::include_wgsl!("../background.wgsl"));
+        let layout = BindGroupBuilder::new("Background Bind Group layout")
+            .add_texture_float_filterable_d2(wgpu::ShaderStages::FRAGMENT, false)
+            .add_sampler_filterable(wgpu::ShaderStages::FRAGMENT)
+            .build(ctx);
+        let pipeline = PipelineBuilder::for_render("Background Pipeline", &shader)
+            .vertex(&[])
+            .fragment(
+                "fs_main",
+                &[Some(wgpu::ColorTargetState {
+                    format: ctx.format(),
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            )
+            .multisample(sample_count)
+            .add_bind_group_layout(&layout)
+            .build(ctx);
+        Self {
+            pipeline,
+            layout,
+            image: None,
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        ctx: &dyn AnyContext,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        background: &Background,
+    ) {
+        let clear = match background {
+            Background::Color(color) => wgpu::Color {
+                r: color.x as f64,
+                g: color.y as f64,
+                b: color.z as f64,
+                a: color.w as f64,
+            },
+            Background::Image(_) => wgpu::Color::TRANSPARENT,
+        };
+        let image = match background {
+            Background::Image(image) => {
+                self.bind_image(ctx, image);
+                self.image.as_ref().map(|(_, bind_group)| bind_group)
+            }
+            Background::Color(_) => None,
+        };
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Background Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(clear),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        if let Some(bind_group) = image {
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+    }
+
+    fn bind_image(&mut self, ctx: &dyn AnyContext, image: &Rc<image::DynamicImage>) {
+        if !matches!(&self.image, Some((current, _)) if Rc::ptr_eq(current, image)) {
+            let texture =
+                Texture::from_image(ctx.device(), ctx.queue(), image, Some("Background Texture"));
+            let bind_group = self.layout.attach(
+                ctx,
+                "Background Bind Group",
+                vec![
+                    wgpu::BindingResource::TextureView(&texture.view),
+                    wgpu::BindingResource::Sampler(&texture.sampler),
+                ],
+            );
+            self.image = Some((image.clone(), bind_group));
+        }
+    }
+}
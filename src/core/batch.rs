@@ -0,0 +1,354 @@
+use std::ops::Range;
+
+use cgmath::Vector3;
+use encase::{DynamicUniformBuffer, ShaderType, StorageBuffer};
+use wgpu::util::DeviceExt;
+
+use super::{stroke::StrokeVertex, AnyContext, Attach, ObjectId, ObjectUniforms, Path, Renderable};
+
+/// Size of a vertex produced by the compute shader, shared with stroke meshes.
+pub const VERTEX_SIZE: u64 = std::mem::size_of::<StrokeVertex>() as u64;
+/// Stride of `array<vec3<f32>>`.
+const POINT_SIZE: u64 = 16;
+/// Pivot of move segments, see compute.wgsl.
+const MOVE: u32 = u32::MAX;
+
+/// Where the geometry of an object lives in the shared buffers of a [`Batch`].
+#[derive(Debug, Clone)]
+pub struct Slot {
+    /// Id of the object, to notice when objects are added, removed or reordered. Unlike
+    /// addresses and revisions, ids are never shared by two objects.
+    key: ObjectId,
+    revision: u64,
+    /// Quadratic segments, each computed into 4 vertices and 6 indices.
+    pub segments: Range<u32>,
+    pub stroke_vertices: Range<u32>,
+    pub stroke_indices: Range<u32>,
+}
+
+impl Slot {
+    fn same_place(&self, other: &Slot) -> bool {
+        self.key == other.key
+            && self.segments == other.segments
+            && self.stroke_vertices == other.stroke_vertices
+            && self.stroke_indices == other.stroke_indices
+    }
+
+    /// Indices of the filled triangles.
+    pub fn fill_indices(&self) -> Range<u32> {
+        self.segments.start * 6..self.segments.end * 6
+    }
+}
+
+pub struct FillBuffers {
+    points: wgpu::Buffer,
+    pivots: wgpu::Buffer,
+    pub vertices: wgpu::Buffer,
+    pub indices: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    pub segments: u32,
+}
+
+pub struct StrokeBuffers {
+    pub vertices: wgpu::Buffer,
+    pub indices: wgpu::Buffer,
+}
+
+pub struct UniformBuffers {
+    buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    /// Dynamic offset of each object's uniforms.
+    pub offsets: Vec<u32>,
+}
+
+/// Geometry and uniforms of all the objects of a scene, packed into shared buffers so they are
+/// computed in a single dispatch and drawn in a single render pass.
+///
+/// The points of consecutive objects are linked by a move segment like subpaths are, so the
+/// compute shader treats them as one path. Objects keep their place while their sizes don't
+/// change, and only the ones with a new revision are uploaded again.
+#[derive(Default)]
+pub struct Batch {
+    /// Per object, in draw order.
+    pub slots: Vec<Slot>,
+    pub fill: Option<FillBuffers>,
+    pub stroke: Option<StrokeBuffers>,
+    pub uniforms: Option<UniformBuffers>,
+}
+
+impl Batch {
+    /// Packs the points and stroke meshes of `objects`, returning whether vertices have to be
+    /// computed from new points.
+    pub fn update_geometry(
+        &mut self,
+        ctx: &dyn AnyContext,
+        compute_layout: &wgpu::BindGroupLayout,
+        objects: &[(ObjectId, &dyn Renderable)],
+    ) -> bool {
+        let (mut segments, mut vertices, mut indices) = (0, 0, 0);
+        let slots = objects
+            .iter()
+            .map(|&(key, object)| {
+                let n = object.points().segments() as u32;
+                if n > 0 && segments > 0 {
+                    segments += 1;
+                }
+                let mesh = object.stroke_mesh();
+                let slot = Slot {
+                    key,
+                    revision: object.revision(),
+                    segments: segments..segments + n,
+                    stroke_vertices: vertices..vertices + mesh.vertices.len() as u32,
+                    stroke_indices: indices..indices + mesh.indices.len() as u32,
+                };
+                segments = slot.segments.end;
+                vertices = slot.stroke_vertices.end;
+                indices = slot.stroke_indices.end;
+                slot
+            })
+            .collect::<Vec<_>>();
+
+        let relayout = slots.len() != self.slots.len()
+            || slots.iter().zip(&self.slots).any(|(a, b)| !a.same_place(b));
+        let changed = match relayout {
+            true => {
+                self.pack(ctx, compute_layout, objects, &slots);
+                segments > 0
+            }
+            false => {
+                let mut changed = false;
+                for ((slot, old), (_, object)) in slots.iter().zip(&self.slots).zip(objects) {
+                    if slot.revision != old.revision {
+                        self.write(ctx, slot, *object);
+                        changed |= !slot.segments.is_empty();
+                    }
+                }
+                changed
+            }
+        };
+        self.slots = slots;
+        changed
+    }
+
+    fn encode_points(points: &[Vector3<f32>]) -> Vec<u8> {
+        let mut data = StorageBuffer::new(Vec::new());
+        data.write(points).unwrap();
+        data.into_inner()
+    }
+
+    /// Pivots of the segments of `path`, placed at segment `base`.
+    fn pivots(path: &Path, base: u32) -> impl Iterator<Item = u32> {
+        path.segment_pivots()
+            .into_iter()
+            .map(move |pivot| if pivot == MOVE { MOVE } else { pivot + base })
+    }
+
+    /// Uploads the geometry of a single object in place.
+    fn write(&self, ctx: &dyn AnyContext, slot: &Slot, object: &dyn Renderable) {
+        let queue = ctx.queue();
+        if let Some(fill) = self.fill.as_ref().filter(|_| !slot.segments.is_empty()) {
+            let start = slot.segments.start as u64;
+            let pivots = Self::pivots(object.points(), slot.segments.start).collect::<Vec<_>>();
+            queue.write_buffer(
+                &fill.points,
+                start * 2 * POINT_SIZE,
+                &Self::encode_points(object.points()),
+            );
+            queue.write_buffer(&fill.pivots, start * 4, bytemuck::cast_slice(&pivots));
+        }
+        if let Some(stroke) = self
+            .stroke
+            .as_ref()
+            .filter(|_| !slot.stroke_indices.is_empty())
+        {
+            let mesh = object.stroke_mesh();
+            queue.write_buffer(
+                &stroke.vertices,
+                slot.stroke_vertices.start as u64 * VERTEX_SIZE,
+                bytemuck::cast_slice(&mesh.vertices),
+            );
+            queue.write_buffer(
+                &stroke.indices,
+                slot.stroke_indices.start as u64 * 4,
+                bytemuck::cast_slice(&mesh.indices),
+            );
+        }
+    }
+
+    /// Uploads the geometry of all objects, reusing buffers of the same size.
+    fn pack(
+        &mut self,
+        ctx: &dyn AnyContext,
+        compute_layout: &wgpu::BindGroupLayout,
+        objects: &[(ObjectId, &dyn Renderable)],
+        slots: &[Slot],
+    ) {
+        let mut points = Vec::<Vector3<f32>>::new();
+        let mut pivots = Vec::new();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for (slot, (_, object)) in slots.iter().zip(objects) {
+            let path = object.points();
+            if path.segments() > 0 {
+                if let Some(&last) = points.last() {
+                    points.push((last + path[0]) * 0.5);
+                    pivots.push(MOVE);
+                }
+                points.extend_from_slice(path);
+                pivots.extend(Self::pivots(path, slot.segments.start));
+            }
+            let mesh = object.stroke_mesh();
+            vertices.extend_from_slice(&mesh.vertices);
+            indices.extend_from_slice(&mesh.indices);
+        }
+
+        self.fill = (!pivots.is_empty()).then(|| {
+            let data = Self::encode_points(&points);
+            let pivots = bytemuck::cast_slice(&pivots);
+            match self.fill.take() {
+                Some(fill) if fill.points.size() == data.len() as u64 => {
+                    ctx.queue().write_buffer(&fill.points, 0, &data);
+                    ctx.queue().write_buffer(&fill.pivots, 0, pivots);
+                    fill
+                }
+                _ => Self::create_fill(ctx, compute_layout, &data, pivots),
+            }
+        });
+
+        self.stroke = (!indices.is_empty()).then(|| {
+            let (vertices, indices) = (
+                bytemuck::cast_slice(&vertices),
+                bytemuck::cast_slice(&indices),
+            );
+            match self.stroke.take() {
+                Some(stroke)
+                    if stroke.vertices.size() == vertices.len() as u64
+                        && stroke.indices.size() == indices.len() as u64 =>
+                {
+                    ctx.queue().write_buffer(&stroke.vertices, 0, vertices);
+                    ctx.queue().write_buffer(&stroke.indices, 0, indices);
+                    stroke
+                }
+                _ => StrokeBuffers {
+                    vertices: Self::create_buffer(
+                        ctx,
+                        "Stroke Vertex Buffer",
+                        vertices,
+                        wgpu::BufferUsages::VERTEX,
+                    ),
+                    indices: Self::create_buffer(
+                        ctx,
+                        "Stroke Index Buffer",
+                        indices,
+                        wgpu::BufferUsages::INDEX,
+                    ),
+                },
+            }
+        });
+    }
+
+    fn create_buffer(
+        ctx: &dyn AnyContext,
+        label: &str,
+        contents: &[u8],
+        usage: wgpu::BufferUsages,
+    ) -> wgpu::Buffer {
+        ctx.device()
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: usage | wgpu::BufferUsages::COPY_DST,
+            })
+    }
+
+    fn create_fill(
+        ctx: &dyn AnyContext,
+        compute_layout: &wgpu::BindGroupLayout,
+        points: &[u8],
+        pivots: &[u8],
+    ) -> FillBuffers {
+        let segments = (pivots.len() / 4) as u32;
+        let create_output = |label, size, usage| {
+            ctx.device().create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage: usage | wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+        };
+        let points = Self::create_buffer(ctx, "Point Buffer", points, wgpu::BufferUsages::STORAGE);
+        let pivots = Self::create_buffer(ctx, "Pivot Buffer", pivots, wgpu::BufferUsages::STORAGE);
+        let vertices = create_output(
+            "Vertex Buffer",
+            segments as u64 * 4 * VERTEX_SIZE,
+            wgpu::BufferUsages::VERTEX,
+        );
+        let indices = create_output(
+            "Index Buffer",
+            segments as u64 * 6 * 4,
+            wgpu::BufferUsages::INDEX,
+        );
+        let bind_group = compute_layout.attach(
+            ctx,
+            "Compute Bind Group",
+            vec![
+                points.as_entire_binding(),
+                vertices.as_entire_binding(),
+                indices.as_entire_binding(),
+                pivots.as_entire_binding(),
+            ],
+        );
+        FillBuffers {
+            points,
+            pivots,
+            vertices,
+            indices,
+            bind_group,
+            segments,
+        }
+    }
+
+    /// Writes the uniforms of all objects into one buffer, bound at a dynamic offset per object.
+    pub fn update_uniforms(
+        &mut self,
+        ctx: &dyn AnyContext,
+        layout: &wgpu::BindGroupLayout,
+        objects: &[(ObjectId, &dyn Renderable)],
+    ) {
+        let alignment = ctx.device().limits().min_uniform_buffer_offset_alignment;
+        let mut data = DynamicUniformBuffer::new_with_alignment(Vec::new(), alignment as u64);
+        let offsets = objects
+            .iter()
+            .map(|(_, object)| data.write(object.uniforms()).unwrap() as u32)
+            .collect::<Vec<_>>();
+        let data = data.into_inner();
+        if data.is_empty() {
+            return;
+        }
+
+        match self.uniforms.as_mut() {
+            Some(uniforms) if uniforms.buffer.size() == data.len() as u64 => {
+                ctx.queue().write_buffer(&uniforms.buffer, 0, &data);
+                uniforms.offsets = offsets;
+            }
+            _ => {
+                let buffer =
+                    Self::create_buffer(ctx, "Object Uniforms", &data, wgpu::BufferUsages::UNIFORM);
+                let bind_group = layout.attach(
+                    ctx,
+                    "Object Uniforms Bind Group",
+                    vec![wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &buffer,
+                        offset: 0,
+                        size: Some(ObjectUniforms::min_size()),
+                    })],
+                );
+                self.uniforms = Some(UniformBuffers {
+                    buffer,
+                    bind_group,
+                    offsets,
+                });
+            }
+        }
+    }
+}
@@ -5,7 +5,7 @@ use winit::{
     keyboard::{KeyCode, PhysicalKey},
 };
 
-use crate::core::{AnyContext, Attach, BindGroupBuilder, SurfaceContext};
+use crate::core::{AnyContext, Attach, BindGroupBuilder};
 
 pub struct Camera {
     eye: Point3<f32>,
@@ -40,10 +40,10 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
 );
 
 impl Camera {
-    pub fn new(ctx: &SurfaceContext<'_>) -> Self {
+    pub fn new(ctx: &(impl AnyContext + ?Sized)) -> Self {
         let uniform = CameraUniform::new();
         let buffer = ctx
-            .device
+            .device()
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Camera Buffer"),
                 contents: bytemuck::cast_slice(&[uniform]),
@@ -80,7 +80,7 @@ impl Camera {
         }
     }
 
-    fn build_view_projection_matrix(&self) -> cgmath::Matrix4<f32> {
+    pub fn build_view_projection_matrix(&self) -> cgmath::Matrix4<f32> {
         let view = cgmath::Matrix4::look_to_rh(self.eye, self.forward, self.up);
         let proj = cgmath::perspective(cgmath::Deg(self.fovy), self.aspect, self.znear, self.zfar);
         OPENGL_TO_WGPU_MATRIX * proj * view
@@ -151,7 +151,7 @@ impl Camera {
         }
     }
 
-    pub fn update_camera(&mut self, ctx: &impl AnyContext) {
+    pub fn update_camera(&mut self) {
         use cgmath::InnerSpace;
         if self.is_forward_pressed {
             self.eye += self.forward * self.speed;
@@ -173,6 +173,9 @@ impl Camera {
         if self.is_down_pressed {
             self.eye -= self.up * self.speed;
         }
+    }
+
+    pub fn write_buffer(&mut self, ctx: &(impl AnyContext + ?Sized)) {
         self.uniform.view_proj = self.build_view_projection_matrix().into();
         ctx.queue()
             .write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[self.uniform]));
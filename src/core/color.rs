@@ -0,0 +1,12 @@
+use cgmath::Vector4;
+
+pub const WHITE: Vector4<f32> = Vector4::new(1.0, 1.0, 1.0, 1.0);
+pub const BLACK: Vector4<f32> = Vector4::new(0.0, 0.0, 0.0, 1.0);
+pub const GRAY: Vector4<f32> = Vector4::new(0.5, 0.5, 0.5, 1.0);
+pub const RED: Vector4<f32> = Vector4::new(0.99, 0.38, 0.33, 1.0);
+pub const GREEN: Vector4<f32> = Vector4::new(0.51, 0.76, 0.31, 1.0);
+pub const BLUE: Vector4<f32> = Vector4::new(0.35, 0.77, 0.95, 1.0);
+pub const YELLOW: Vector4<f32> = Vector4::new(1.0, 1.0, 0.0, 1.0);
+pub const ORANGE: Vector4<f32> = Vector4::new(1.0, 0.53, 0.24, 1.0);
+pub const PURPLE: Vector4<f32> = Vector4::new(0.6, 0.4, 0.8, 1.0);
+pub const TRANSPARENT: Vector4<f32> = Vector4::new(0.0, 0.0, 0.0, 0.0);
@@ -0,0 +1,245 @@
+use std::fmt::Write;
+
+use cgmath::{InnerSpace, Matrix4, Vector2, Vector3, Vector4};
+
+use super::{BlendMode, FillRule, Gradient, GradientKind, LineCap, LineJoin, Path, Stroke};
+
+/// How an area is painted.
+#[derive(Debug, Clone)]
+pub enum Paint {
+    Color(Vector4<f32>),
+    Gradient(Gradient),
+}
+
+/// CPU-side description of an object: its quadratic Bezier points in local space, the model
+/// matrix placing them in the world and how they are painted.
+///
+/// Texture fills are left out, leaving the color they are multiplied with.
+#[derive(Debug, Clone)]
+pub struct Outline {
+    pub points: Path,
+    pub model: Matrix4<f32>,
+    pub fill: Option<Paint>,
+    pub fill_rule: FillRule,
+    pub blend_mode: BlendMode,
+    pub stroke: Option<(Stroke, Vector4<f32>)>,
+}
+
+/// Writes outlines as SVG paths, as seen by the camera on a `size` pixel frame.
+///
+/// Points are projected individually, which is exact for shapes facing the camera and an
+/// approximation for curves seen at an angle.
+pub(crate) struct SvgWriter {
+    view_projection: Matrix4<f32>,
+    size: (u32, u32),
+    /// Colors are linear and encoded on output, as done by sRGB render targets.
+    srgb: bool,
+    defs: String,
+    paths: String,
+    gradients: usize,
+}
+
+impl SvgWriter {
+    pub fn new(view_projection: Matrix4<f32>, size: (u32, u32), srgb: bool) -> Self {
+        Self {
+            view_projection,
+            size,
+            srgb,
+            defs: String::new(),
+            paths: String::new(),
+            gradients: 0,
+        }
+    }
+
+    /// Pixel position of a local point, `None` behind the camera.
+    fn project(
+        transform: &Matrix4<f32>,
+        size: (u32, u32),
+        p: Vector3<f32>,
+    ) -> Option<Vector2<f32>> {
+        let clip = transform * p.extend(1.);
+        (clip.w > 0.).then(|| {
+            Vector2::new(
+                (clip.x / clip.w + 1.) / 2. * size.0 as f32,
+                (1. - clip.y / clip.w) / 2. * size.1 as f32,
+            )
+        })
+    }
+
+    /// Hex color and opacity.
+    fn color(&self, color: Vector4<f32>) -> (String, f32) {
+        let encode = |c: f32| {
+            let c = c.clamp(0., 1.);
+            let c = match self.srgb {
+                true if c <= 0.0031308 => c * 12.92,
+                true => 1.055 * c.powf(1. / 2.4) - 0.055,
+                false => c,
+            };
+            (c * 255.).round() as u8
+        };
+        let hex = format!(
+            "#{:02x}{:02x}{:02x}",
+            encode(color.x),
+            encode(color.y),
+            encode(color.z)
+        );
+        (hex, color.w.clamp(0., 1.))
+    }
+
+    /// Paint attribute value, adding gradients to the definitions.
+    fn paint(
+        &mut self,
+        paint: &Paint,
+        project: impl Fn(Vector2<f32>) -> Vector2<f32>,
+    ) -> (String, f32) {
+        let gradient = match paint {
+            Paint::Color(color) => return self.color(*color),
+            Paint::Gradient(gradient) => gradient,
+        };
+        let id = format!("gradient{}", self.gradients);
+        self.gradients += 1;
+        let element = match gradient.kind {
+            GradientKind::Linear { start, end } => {
+                let (start, end) = (project(start), project(end));
+                let _ = write!(
+                    self.defs,
+                    r#"<linearGradient id="{id}" gradientUnits="userSpaceOnUse" x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}">"#,
+                    start.x, start.y, end.x, end.y
+                );
+                "linearGradient"
+            }
+            GradientKind::Radial { center, radius } => {
+                let c = project(center);
+                let r = (project(center + Vector2::new(radius, 0.)) - c).magnitude();
+                let _ = write!(
+                    self.defs,
+                    r#"<radialGradient id="{id}" gradientUnits="userSpaceOnUse" cx="{:.2}" cy="{:.2}" r="{r:.2}">"#,
+                    c.x, c.y
+                );
+                "radialGradient"
+            }
+        };
+        for &(offset, color) in &gradient.stops {
+            let (hex, opacity) = self.color(color);
+            let _ = write!(
+                self.defs,
+                r#"<stop offset="{offset}" stop-color="{hex}" stop-opacity="{opacity}"/>"#
+            );
+        }
+        let _ = writeln!(self.defs, "</{element}>");
+        (format!("url(#{id})"), 1.)
+    }
+
+    /// Fills the whole document with a color, drawn below every path.
+    pub fn background(&mut self, color: Vector4<f32>) {
+        if color.w <= 0. {
+            return;
+        }
+        let (hex, opacity) = self.color(color);
+        let mut rect = format!(r#"<rect width="100%" height="100%" fill="{hex}""#);
+        if opacity < 1. {
+            let _ = write!(rect, r#" fill-opacity="{opacity}""#);
+        }
+        rect.push_str("/>\n");
+        self.paths.insert_str(0, &rect);
+    }
+
+    pub fn add(&mut self, outline: &Outline) {
+        let points = &outline.points;
+        if points.segments() == 0 || (outline.fill.is_none() && outline.stroke.is_none()) {
+            return;
+        }
+        let transform = self.view_projection * outline.model;
+        let size = self.size;
+        let mut subpaths = Vec::new();
+        for subpath in points.subpaths() {
+            let Some(projected) = subpath
+                .iter()
+                .map(|&p| Self::project(&transform, size, p))
+                .collect::<Option<Vec<_>>>()
+            else {
+                return;
+            };
+            let mut d = format!("M{:.2} {:.2}", projected[0].x, projected[0].y);
+            for segment in projected[1..].chunks_exact(2) {
+                let (c, p) = (segment[0], segment[1]);
+                let _ = write!(d, " Q{:.2} {:.2} {:.2} {:.2}", c.x, c.y, p.x, p.y);
+            }
+            let closed = (subpath[0] - subpath[subpath.len() - 1]).magnitude() < 1e-4;
+            if closed {
+                d.push_str(" Z");
+            }
+            subpaths.push(d);
+        }
+        let mut path = format!(r#"<path d="{}""#, subpaths.join(" "));
+
+        let project_local = |p: Vector2<f32>| {
+            Self::project(&transform, size, p.extend(0.)).unwrap_or(Vector2::new(0., 0.))
+        };
+        match &outline.fill {
+            Some(paint) => {
+                let (fill, opacity) = self.paint(paint, project_local);
+                let _ = write!(path, r#" fill="{fill}""#);
+                if opacity < 1. {
+                    let _ = write!(path, r#" fill-opacity="{opacity}""#);
+                }
+                path.push_str(match outline.fill_rule {
+                    FillRule::EvenOdd => r#" fill-rule="evenodd""#,
+                    FillRule::NonZero => r#" fill-rule="nonzero""#,
+                });
+            }
+            None => path.push_str(r#" fill="none""#),
+        }
+
+        if let Some((stroke, color)) = &outline.stroke {
+            // Widths are in local units, scaled by the average stretch around the shape
+            let center = points.iter().sum::<Vector3<f32>>() / points.len() as f32;
+            let o = project_local(center.truncate());
+            let x = project_local(center.truncate() + Vector2::unit_x()) - o;
+            let y = project_local(center.truncate() + Vector2::unit_y()) - o;
+            let width = stroke.width * x.perp_dot(y).abs().sqrt();
+
+            let (hex, opacity) = self.color(*color);
+            let cap = match stroke.cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            };
+            let join = match stroke.join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            };
+            let _ = write!(
+                path,
+                r#" stroke="{hex}" stroke-width="{width:.2}" stroke-linecap="{cap}" stroke-linejoin="{join}" stroke-miterlimit="{}""#,
+                stroke.miter_limit
+            );
+            if opacity < 1. {
+                let _ = write!(path, r#" stroke-opacity="{opacity}""#);
+            }
+        }
+        // Additive blending is the compositing operator closest to adding colors
+        path.push_str(match outline.blend_mode {
+            BlendMode::Normal => "",
+            BlendMode::Additive => r#" style="mix-blend-mode:plus-lighter""#,
+            BlendMode::Multiply => r#" style="mix-blend-mode:multiply""#,
+            BlendMode::Screen => r#" style="mix-blend-mode:screen""#,
+        });
+        path.push_str("/>\n");
+        self.paths.push_str(&path);
+    }
+
+    pub fn finish(self) -> String {
+        let (width, height) = self.size;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        if !self.defs.is_empty() {
+            svg += &format!("<defs>\n{}</defs>\n", self.defs);
+        }
+        svg += &self.paths;
+        svg += "</svg>\n";
+        svg
+    }
+}
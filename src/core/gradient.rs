@@ -0,0 +1,174 @@
+use cgmath::{Vector2, Vector4, VectorSpace};
+use encase::ShaderType;
+
+/// Fill that varies over a shape, in the shape's local coordinates.
+///
+/// Built like `Gradient::linear((-1., 0.), (1., 0.)).stop(0., RED).stop(1., BLUE)`.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub stops: Vec<(f32, Vector4<f32>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientKind {
+    Linear {
+        start: Vector2<f32>,
+        end: Vector2<f32>,
+    },
+    Radial {
+        center: Vector2<f32>,
+        radius: f32,
+    },
+}
+
+impl Gradient {
+    pub const MAX_STOPS: usize = 4;
+
+    pub fn linear(start: impl Into<Vector2<f32>>, end: impl Into<Vector2<f32>>) -> Self {
+        Self {
+            kind: GradientKind::Linear {
+                start: start.into(),
+                end: end.into(),
+            },
+            stops: Vec::new(),
+        }
+    }
+
+    pub fn radial(center: impl Into<Vector2<f32>>, radius: f32) -> Self {
+        Self {
+            kind: GradientKind::Radial {
+                center: center.into(),
+                radius,
+            },
+            stops: Vec::new(),
+        }
+    }
+
+    /// Adds a color at `offset`, 0 being the start (center) and 1 the end (radius).
+    pub fn stop(mut self, offset: f32, color: impl Into<Vector4<f32>>) -> Self {
+        assert!(
+            self.stops.len() < Self::MAX_STOPS,
+            "Gradients support at most {} stops",
+            Self::MAX_STOPS
+        );
+        self.stops.push((offset.clamp(0., 1.), color.into()));
+        self.stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+}
+
+/// GPU side of a [`Gradient`]. Unused stops repeat the last one, so the shader can always
+/// blend all of them and two gradients can be interpolated stop by stop.
+#[derive(Debug, ShaderType, Clone, Copy)]
+pub struct GradientUniforms {
+    colors: [Vector4<f32>; Gradient::MAX_STOPS],
+    offsets: Vector4<f32>,
+    start: Vector2<f32>,
+    end: Vector2<f32>,
+    radius: f32,
+    /// 0 for a solid fill, 1 for linear and 2 for radial gradients.
+    kind: u32,
+}
+
+impl GradientUniforms {
+    const SOLID: u32 = 0;
+    const LINEAR: u32 = 1;
+    const RADIAL: u32 = 2;
+
+    pub fn solid() -> Self {
+        Self {
+            colors: [Vector4::new(0., 0., 0., 0.); Gradient::MAX_STOPS],
+            offsets: Vector4::new(0., 0., 0., 0.),
+            start: Vector2::new(0., 0.),
+            end: Vector2::new(0., 0.),
+            radius: 0.,
+            kind: Self::SOLID,
+        }
+    }
+
+    pub fn is_solid(&self) -> bool {
+        self.kind == Self::SOLID
+    }
+
+    /// The gradient these uniforms describe, `None` for a solid fill.
+    pub(crate) fn to_gradient(self) -> Option<Gradient> {
+        let kind = match self.kind {
+            Self::LINEAR => GradientKind::Linear {
+                start: self.start,
+                end: self.end,
+            },
+            Self::RADIAL => GradientKind::Radial {
+                center: self.start,
+                radius: self.radius,
+            },
+            _ => return None,
+        };
+        let stops = (0..Gradient::MAX_STOPS)
+            .map(|i| (self.offsets[i], self.colors[i]))
+            .collect();
+        Some(Gradient { kind, stops })
+    }
+
+    /// Same geometry as `self` but every stop is `color`.
+    fn with_color(&self, color: Vector4<f32>) -> Self {
+        Self {
+            colors: [color; Gradient::MAX_STOPS],
+            ..*self
+        }
+    }
+
+    /// Interpolates two fills, each either this gradient or the given solid color.
+    ///
+    /// A solid color morphs into a gradient by taking on its geometry first.
+    pub fn lerp(
+        (a, a_color): (&Self, Vector4<f32>),
+        (b, b_color): (&Self, Vector4<f32>),
+        t: f32,
+    ) -> Self {
+        let (a, b) = match (a.is_solid(), b.is_solid()) {
+            (true, true) => return Self::solid(),
+            (true, false) => (b.with_color(a_color), *b),
+            (false, true) => (*a, a.with_color(b_color)),
+            (false, false) => (*a, *b),
+        };
+        let mut colors = a.colors;
+        for (color, b) in colors.iter_mut().zip(b.colors) {
+            *color = color.lerp(b, t);
+        }
+        Self {
+            colors,
+            offsets: a.offsets.lerp(b.offsets, t),
+            start: a.start.lerp(b.start, t),
+            end: a.end.lerp(b.end, t),
+            radius: a.radius + (b.radius - a.radius) * t,
+            kind: if t < 0.5 { a.kind } else { b.kind },
+        }
+    }
+}
+
+impl From<Gradient> for GradientUniforms {
+    fn from(gradient: Gradient) -> Self {
+        let Some(&last) = gradient.stops.last() else {
+            return Self::solid();
+        };
+        let mut colors = [last.1; Gradient::MAX_STOPS];
+        let mut offsets = [last.0; Gradient::MAX_STOPS];
+        for (i, &(offset, color)) in gradient.stops.iter().enumerate() {
+            offsets[i] = offset;
+            colors[i] = color;
+        }
+        let (kind, start, end, radius) = match gradient.kind {
+            GradientKind::Linear { start, end } => (Self::LINEAR, start, end, 0.),
+            GradientKind::Radial { center, radius } => (Self::RADIAL, center, center, radius),
+        };
+        Self {
+            colors,
+            offsets: offsets.into(),
+            start,
+            end,
+            radius,
+            kind,
+        }
+    }
+}
@@ -1,36 +1,67 @@
+mod background;
+mod batch;
 mod camera;
+pub mod color;
+mod export;
+mod gradient;
+mod path;
 mod renderer;
 mod shape;
+mod stroke;
 mod utils;
+mod video;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::rc::Rc;
 use std::time::Duration;
 
-use crate::animations::Animatable;
+use crate::animations::{Animatable, AnimationGroup, BackgroundTransition, Wait, WaitUntil};
 use crate::texture::Texture;
+pub use background::Background;
+use background::BackgroundRenderer;
 use camera::Camera;
 use cgmath::Matrix4;
 use cgmath::SquareMatrix;
 use cgmath::Vector4;
 use encase::ShaderType;
+use export::SvgWriter;
+pub use export::{Outline, Paint};
 use futures::channel::oneshot;
+pub use gradient::{Gradient, GradientKind, GradientUniforms};
+pub use path::{FillRule, Path};
 use renderer::QBezierRenderer;
+pub use renderer::{BlendMode, DepthMode};
 pub use shape::HasPoints;
 pub use shape::Mobject;
 pub use shape::Renderable;
 pub use shape::Shape;
 use shape::Transform;
+pub use stroke::{tessellate, LineCap, LineJoin, Stroke, StrokeMesh, StrokeVertex};
 pub use utils::bindgroup::{Attach, BindGroupBuilder};
 pub use utils::context::AnyContext;
 pub use utils::context::Context;
+pub use utils::context::Frame;
+pub use utils::context::RenderContext;
 pub use utils::context::SurfaceContext;
+pub use utils::context::TextureContext;
 pub use utils::pipeline::PipelineBuilder;
+pub use video::{VideoOutput, VideoRenderer};
 
 #[derive(Debug, ShaderType, Clone)]
 pub struct ObjectUniforms {
     pub model: Matrix4<f32>,
     pub color: Vector4<f32>,
+    pub stroke_color: Vector4<f32>,
+    /// Overrides `color` unless solid.
+    pub gradient: GradientUniforms,
+    /// Local bounding box of the points as `(min.x, min.y, max.x, max.y)`, texture fills span it.
+    pub bounds: Vector4<f32>,
+    /// Non-zero if the fill is multiplied with the shape's texture.
+    pub textured: u32,
+    /// Multiplied with the alpha of the fill, on top of its color.
+    pub fill_opacity: f32,
+    pub stroke_opacity: f32,
 }
 
 impl Default for ObjectUniforms {
@@ -38,6 +69,12 @@ impl Default for ObjectUniforms {
         Self {
             model: Matrix4::identity(),
             color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            stroke_color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            gradient: GradientUniforms::solid(),
+            bounds: Vector4::new(0.0, 0.0, 0.0, 0.0),
+            textured: 0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
         }
     }
 }
@@ -47,6 +84,12 @@ impl ObjectUniforms {
         Self {
             model: transform.get_matrix(),
             color,
+            stroke_color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            gradient: GradientUniforms::solid(),
+            bounds: Vector4::new(0.0, 0.0, 0.0, 0.0),
+            textured: 0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
         }
     }
 }
@@ -74,37 +117,140 @@ impl<'a> Deref for Scene<'a> {
 }
 
 impl<'a> Scene<'a> {
-    pub fn new(ctx: SurfaceContext<'a>) -> Self {
+    pub fn new(ctx: impl RenderContext + 'a) -> Self {
+        Self::with_msaa(ctx, 1)
+    }
+
+    /// Creates a scene rendered with `sample_count` samples per pixel to antialias edges.
+    /// WebGPU guarantees support for 1 (no antialiasing) and 4.
+    pub fn with_msaa(ctx: impl RenderContext + 'a, sample_count: u32) -> Self {
+        assert!(
+            matches!(sample_count, 1 | 4),
+            "Unsupported MSAA sample count {sample_count}"
+        );
         Self {
-            inner: Rc::new(RefCell::new(InnerScene::new(ctx))),
+            inner: Rc::new(RefCell::new(InnerScene::new(Box::new(ctx), sample_count))),
         }
     }
 
-    pub fn add<T: HasPoints + 'a>(&self, shape: &Mobject<T>) {
-        self.inner.borrow_mut().add(shape);
+    /// Adds the mobject if it is not in the scene yet, returning its id either way.
+    pub fn add<T: HasPoints + 'a>(&self, shape: &Mobject<T>) -> ObjectId {
+        self.inner.borrow_mut().add(shape)
+    }
+
+    /// Returns whether the mobject was in the scene.
+    pub fn remove<T: HasPoints + 'a>(&self, shape: &Mobject<T>) -> bool {
+        self.inner.borrow_mut().remove(shape)
     }
 
-    pub fn remove<T: HasPoints + 'a>(&self, shape: Mobject<T>) {
-        self.inner.borrow_mut().remove(shape);
+    pub fn remove_by_id(&self, id: ObjectId) -> bool {
+        self.inner.borrow_mut().remove_by_id(id)
+    }
+
+    pub fn id_of<T: HasPoints + 'a>(&self, shape: &Mobject<T>) -> Option<ObjectId> {
+        self.inner.borrow().id_of(shape)
+    }
+
+    /// Draws the mobject after the others on its layer, returning whether it is in the scene.
+    pub fn bring_to_front<T: HasPoints + 'a>(&self, shape: &Mobject<T>) -> bool {
+        self.inner.borrow_mut().reorder(shape, true)
+    }
+
+    /// Draws the mobject before the others on its layer, returning whether it is in the scene.
+    pub fn send_to_back<T: HasPoints + 'a>(&self, shape: &Mobject<T>) -> bool {
+        self.inner.borrow_mut().reorder(shape, false)
+    }
+
+    /// Sets what frames are cleared to, e.g. a color `(0.1, 0.1, 0.1, 1.)` or an image.
+    pub fn set_background(&self, background: impl Into<Background>) {
+        *self.inner.borrow().background.borrow_mut() = background.into();
+    }
+
+    /// Fades the background to `color`, see [`BackgroundTransition`].
+    pub fn animate_background(
+        &self,
+        color: impl Into<Vector4<f32>>,
+        duration: f32,
+    ) -> BackgroundTransition {
+        let background = self.inner.borrow().background.clone();
+        BackgroundTransition::new(background, color.into(), duration)
+    }
+
+    pub fn set_depth_mode(&self, depth_mode: DepthMode) {
+        self.inner.borrow_mut().depth_mode = depth_mode;
     }
 
     pub async fn play(&self, anim: impl Animatable + 'a) {
         let rx = self.inner.borrow_mut().play(anim);
         rx.await.unwrap();
     }
+
+    pub async fn play_all(&self, anims: impl IntoIterator<Item = Box<dyn Animatable + 'a>>) {
+        self.play(anims.into_iter().collect::<AnimationGroup>())
+            .await;
+    }
+
+    /// Pauses the script for `seconds` of scene time.
+    pub async fn wait(&self, seconds: f32) {
+        self.play(Wait::new(seconds)).await;
+    }
+
+    /// Pauses the script until `predicate` returns true, checked once per frame.
+    pub async fn wait_until(&self, predicate: impl FnMut() -> bool + 'a) {
+        self.play(WaitUntil::new(predicate)).await;
+    }
+
+    pub fn capture(&self) -> image::RgbaImage {
+        self.inner.borrow_mut().capture()
+    }
+
+    pub fn save_frame(&self, path: impl AsRef<std::path::Path>) -> image::ImageResult<()> {
+        self.capture().save(path)
+    }
+
+    /// Draws the current state as an SVG document instead of rendering it, see
+    /// [`InnerScene::to_svg`].
+    pub fn to_svg(&self) -> String {
+        self.inner.borrow().to_svg()
+    }
+
+    pub fn save_svg(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
 }
 
+/// Stable handle of an object added to a [`Scene`]. Ids are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(u64);
+
 pub struct InnerScene<'a> {
-    ctx: SurfaceContext<'a>,
+    ctx: Box<dyn RenderContext + 'a>,
     camera: Camera,
     depth_texture: Texture,
-    objects: Vec<Rc<RefCell<dyn Renderable + 'a>>>,
-    animation: Option<(Box<dyn Animatable + 'a>, oneshot::Sender<()>)>,
+    sample_count: u32,
+    // Rendered into and resolved to the target view when multisampling
+    msaa_texture: Option<Texture>,
+    objects: HashMap<ObjectId, Rc<RefCell<dyn Renderable + 'a>>>,
+    // Keyed by the address of the mobject's shared cell
+    object_ids: HashMap<*const (), ObjectId>,
+    // Ids in insertion order, possibly including removed ones until the next `prune`
+    draw_order: Vec<ObjectId>,
+    next_id: u64,
+    depth_mode: DepthMode,
+    // Shared with the animations changing it, which run while the scene is borrowed
+    background: Rc<RefCell<Background>>,
+    background_renderer: BackgroundRenderer,
+    animations: Vec<RunningAnimation<'a>>,
     qbezier_renderer: QBezierRenderer,
-    t: f32,
     // mesh_renderer: MeshRenderer,
 }
 
+struct RunningAnimation<'a> {
+    anim: Box<dyn Animatable + 'a>,
+    t: f32,
+    tx: oneshot::Sender<()>,
+}
+
 #[macro_export]
 macro_rules! add {
     ($scene:ident, $($shape:ident),*) => {
@@ -118,101 +264,250 @@ macro_rules! add {
 macro_rules! remove {
     ($scene:ident, $($shape:ident),*) => {
         $(
-            $scene.remove($shape);
+            $scene.remove(&$shape);
         )*
     };
 }
 
 impl<'a> InnerScene<'a> {
-    fn new(ctx: SurfaceContext<'a>) -> Self {
-        let depth_texture = Texture::create_depth_texture(
-            &ctx.device,
-            (ctx.config.width, ctx.config.height),
-            "Depth Texture",
-        );
-        let camera = Camera::new(&ctx);
+    fn new(ctx: Box<dyn RenderContext + 'a>, sample_count: u32) -> Self {
+        let depth_texture =
+            Texture::create_depth_texture(ctx.device(), ctx.size(), sample_count, "Depth Texture");
+        let msaa_texture = Self::create_msaa_texture(ctx.deref(), sample_count);
+        let mut camera = Camera::new(ctx.deref());
+        camera.aspect = ctx.size().0 as f32 / ctx.size().1 as f32;
         Self {
-            objects: Vec::new(),
-            qbezier_renderer: QBezierRenderer::new(&ctx, &camera.bind_group_layout),
+            objects: HashMap::new(),
+            object_ids: HashMap::new(),
+            draw_order: Vec::new(),
+            next_id: 0,
+            depth_mode: DepthMode::default(),
+            background: Rc::default(),
+            background_renderer: BackgroundRenderer::new(ctx.deref(), sample_count),
+            qbezier_renderer: QBezierRenderer::new(
+                ctx.deref(),
+                &camera.bind_group_layout,
+                sample_count,
+            ),
             depth_texture,
+            sample_count,
+            msaa_texture,
             camera,
-            animation: None,
+            animations: Vec::new(),
             ctx,
-            t: 0.,
         }
     }
 
-    fn upcast<T: HasPoints + 'a>(shape: Rc<RefCell<Shape<T>>>) -> Rc<RefCell<dyn Renderable + 'a>> {
-        shape
+    fn create_msaa_texture(ctx: &dyn RenderContext, sample_count: u32) -> Option<Texture> {
+        (sample_count > 1).then(|| {
+            Texture::create_multisampled_target(
+                ctx.device(),
+                ctx.size(),
+                ctx.format(),
+                sample_count,
+                "MSAA Texture",
+            )
+        })
     }
 
-    fn add<T: HasPoints + 'a>(&mut self, shape: &Mobject<T>) {
-        shape
-            .borrow_mut()
-            .create_render_object(&self.ctx, self.qbezier_renderer.render_layout());
-        self.objects.push(shape.deref().clone());
+    fn key<T: HasPoints>(shape: &Mobject<T>) -> *const () {
+        Rc::as_ptr(shape.deref()) as *const ()
     }
 
-    fn remove<T: HasPoints + 'a>(&mut self, shape: Mobject<T>) {
-        // TODO: This is O(n)
-        self.objects
-            .retain(|x| Rc::ptr_eq(x, &Self::upcast(shape.clone())));
+    fn add<T: HasPoints + 'a>(&mut self, shape: &Mobject<T>) -> ObjectId {
+        if let Some(&id) = self.object_ids.get(&Self::key(shape)) {
+            return id;
+        }
+        let id = ObjectId(self.next_id);
+        self.next_id += 1;
+        self.objects.insert(id, shape.deref().clone());
+        self.object_ids.insert(Self::key(shape), id);
+        self.draw_order.push(id);
+        id
     }
 
-    pub fn update(&mut self, dt: Duration) {
-        self.camera.update_camera(&self.ctx);
-        if let Some((anim, _)) = self.animation.as_mut() {
-            if !anim.apply(self.t) {
-                let (_, tx) = self.animation.take().unwrap();
-                tx.send(()).unwrap();
-            }
-            self.t += dt.as_secs_f32();
+    fn remove<T: HasPoints + 'a>(&mut self, shape: &Mobject<T>) -> bool {
+        match self.object_ids.get(&Self::key(shape)) {
+            Some(&id) => self.remove_by_id(id),
+            None => false,
+        }
+    }
+
+    fn remove_by_id(&mut self, id: ObjectId) -> bool {
+        let Some(object) = self.objects.remove(&id) else {
+            return false;
+        };
+        self.object_ids.remove(&(Rc::as_ptr(&object) as *const ()));
+        true
+    }
+
+    fn id_of<T: HasPoints + 'a>(&self, shape: &Mobject<T>) -> Option<ObjectId> {
+        self.object_ids.get(&Self::key(shape)).copied()
+    }
+
+    fn reorder<T: HasPoints + 'a>(&mut self, shape: &Mobject<T>, front: bool) -> bool {
+        let Some(id) = self.id_of(shape) else {
+            return false;
+        };
+        self.draw_order.retain(|&other| other != id);
+        match front {
+            true => self.draw_order.push(id),
+            false => self.draw_order.insert(0, id),
         }
+        true
+    }
+
+    /// Ids of the objects in the scene, in the order they are drawn: by layer, then farthest
+    /// first when depth is tested, then in the order they were added or moved to.
+    pub fn object_ids(&self) -> Vec<ObjectId> {
+        let view_projection = self.camera.build_view_projection_matrix();
+        let mut ids = self
+            .draw_order
+            .iter()
+            .filter_map(|id| self.objects.get(id).map(|object| (id, object.borrow())))
+            .map(|(&id, object)| {
+                let depth = match self.depth_mode {
+                    DepthMode::Painter => 0.,
+                    DepthMode::Tested => {
+                        let clip = view_projection * object.model() * Vector4::unit_w();
+                        clip.z / clip.w
+                    }
+                };
+                (object.z_index(), depth, id)
+            })
+            .collect::<Vec<_>>();
+        // Stable, so ties keep their order
+        ids.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)));
+        ids.into_iter().map(|(_, _, id)| id).collect()
+    }
+
+    /// Drops removed ids from the draw order, amortizing the cost of removals over frames.
+    fn prune(&mut self) {
+        if self.draw_order.len() != self.objects.len() {
+            self.draw_order.retain(|id| self.objects.contains_key(id));
+        }
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        self.camera.update_camera();
+        self.animations = std::mem::take(&mut self.animations)
+            .into_iter()
+            .filter_map(|mut running| {
+                // Animations are in their start state once begun, so frames drawn before an
+                // update show the time reached by the previous one
+                running.t += dt.as_secs_f32();
+                if running.anim.apply(running.t) {
+                    Some(running)
+                } else {
+                    // The receiver is gone if the awaiting future was dropped
+                    let _ = running.tx.send(());
+                    None
+                }
+            })
+            .collect();
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.animations.is_empty()
     }
 
     fn play(&mut self, mut anim: impl Animatable + 'a) -> oneshot::Receiver<()> {
-        self.t = 0.;
         anim.begin();
         let (tx, rx) = oneshot::channel();
-        self.animation = Some((Box::new(anim), tx));
+        self.animations.push(RunningAnimation {
+            anim: Box::new(anim),
+            t: 0.,
+            tx,
+        });
         rx
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let frame = self.ctx.surface.get_current_texture()?;
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let frame = self.ctx.get_current_frame()?;
+        self.render_to(&frame.view);
+        frame.present();
+        Ok(())
+    }
+
+    pub fn render_to(&mut self, view: &wgpu::TextureView) {
+        self.camera.write_buffer(self.ctx.deref());
+        self.prune();
         let mut encoder = self
             .ctx
             .device()
             .create_command_encoder(&Default::default());
 
-        for object in &self.objects {
-            self.qbezier_renderer.render(
-                &self.ctx,
-                &view,
-                &self.depth_texture.view,
-                &self.camera.bind_group,
-                &mut encoder,
-                object,
-                false,
-            );
-        }
+        let (color_view, resolve_target) = match &self.msaa_texture {
+            Some(msaa) => (&msaa.view, Some(view)),
+            None => (view, None),
+        };
+        self.background_renderer.render(
+            self.ctx.deref(),
+            &mut encoder,
+            color_view,
+            resolve_target,
+            &self.background.borrow(),
+        );
+
+        let objects = self
+            .object_ids()
+            .iter()
+            .map(|&id| (id, &*self.objects[&id]))
+            .collect::<Vec<_>>();
+        self.qbezier_renderer.render(
+            self.ctx.deref(),
+            color_view,
+            resolve_target,
+            &self.depth_texture.view,
+            &self.camera.bind_group,
+            &mut encoder,
+            &objects,
+            self.depth_mode,
+        );
 
         self.ctx.queue().submit(std::iter::once(encoder.finish()));
-        frame.present();
-        Ok(())
+    }
+
+    /// Renders the current state into a fresh offscreen texture and reads it back.
+    pub fn capture(&mut self) -> image::RgbaImage {
+        let target = Texture::create_render_target(
+            self.ctx.device(),
+            self.ctx.size(),
+            self.ctx.format(),
+            "Capture Texture",
+        );
+        self.render_to(&target.view);
+        target.read_to_image(self.ctx.device(), self.ctx.queue())
+    }
+
+    /// Draws every object as an SVG `path` through the camera projection, in draw order and
+    /// without the GPU. Texture fills and image backgrounds are left out.
+    pub fn to_svg(&self) -> String {
+        let mut writer = SvgWriter::new(
+            self.camera.build_view_projection_matrix(),
+            self.ctx.size(),
+            self.ctx.format().is_srgb(),
+        );
+        if let Background::Color(color) = *self.background.borrow() {
+            writer.background(color);
+        }
+        for object in self.object_ids().iter().map(|id| &self.objects[id]) {
+            writer.add(&object.borrow().outline());
+        }
+        writer.finish()
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         self.ctx.resize(new_size);
-        self.camera.aspect = self.ctx.config.width as f32 / self.ctx.config.height as f32;
+        let (width, height) = self.ctx.size();
+        self.camera.aspect = width as f32 / height as f32;
         self.depth_texture = Texture::create_depth_texture(
-            &self.ctx.device,
-            (self.ctx.config.width, self.ctx.config.height),
+            self.ctx.device(),
+            (width, height),
+            self.sample_count,
             "depth_texture",
         );
+        self.msaa_texture = Self::create_msaa_texture(self.ctx.deref(), self.sample_count);
     }
 
     pub fn process_inputs(&mut self, event: &winit::event::WindowEvent) {
@@ -0,0 +1,169 @@
+use std::ops::{Deref, DerefMut};
+
+use cgmath::{Vector3, VectorSpace};
+
+/// Quadratic Bezier path made of any number of open or closed subpaths.
+///
+/// The points form one chain `p0, c0, p1, c1, p2, ..` alternating between anchors and control
+/// points. Subpaths are linked by straight move segments, which are neither filled nor
+/// stroked, so two paths with as many points can always be interpolated point by point.
+///
+/// Each subpath is filled as if closed, holes are cut according to the shape's [`FillRule`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    points: Vec<Vector3<f32>>,
+    /// Whether each segment is a move between subpaths.
+    moves: Vec<bool>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_subpaths(subpaths: impl IntoIterator<Item = Vec<Vector3<f32>>>) -> Self {
+        let mut path = Self::new();
+        for subpath in subpaths {
+            path.push_subpath(subpath);
+        }
+        path
+    }
+
+    /// Appends a subpath of quadratic segments, ignoring it if it has none.
+    pub fn push_subpath(&mut self, subpath: Vec<Vector3<f32>>) {
+        if subpath.len() < 3 {
+            return;
+        }
+        debug_assert!(
+            subpath.len() % 2 == 1,
+            "Subpaths are anchor, control, anchor.."
+        );
+        if let Some(&last) = self.points.last() {
+            self.points.push((last + subpath[0]) * 0.5);
+            self.moves.push(true);
+        }
+        self.moves
+            .extend(std::iter::repeat_n(false, subpath.len() / 2));
+        self.points.extend(subpath);
+    }
+
+    /// Number of quadratic segments, including moves.
+    pub fn segments(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_move(&self, segment: usize) -> bool {
+        self.moves[segment]
+    }
+
+    /// The points of each subpath, skipping the moves between them.
+    pub fn subpaths(&self) -> impl Iterator<Item = &[Vector3<f32>]> + '_ {
+        let mut segment = 0;
+        std::iter::from_fn(move || {
+            while self.moves.get(segment) == Some(&true) {
+                segment += 1;
+            }
+            let start = segment;
+            while self.moves.get(segment) == Some(&false) {
+                segment += 1;
+            }
+            (segment > start).then(|| &self.points[start * 2..=segment * 2])
+        })
+    }
+
+    /// For every segment, the first segment of its subpath, or `u32::MAX` for moves.
+    pub(crate) fn segment_pivots(&self) -> Vec<u32> {
+        let mut pivot = 0;
+        (0..self.segments())
+            .map(|segment| match self.moves[segment] {
+                true => u32::MAX,
+                false if segment == 0 || self.moves[segment - 1] => {
+                    pivot = segment as u32;
+                    pivot
+                }
+                false => pivot,
+            })
+            .collect()
+    }
+
+    /// Interpolates the points, taking the subpaths of the nearest end so each end is exactly
+    /// its path. Extra points of the longer path are dropped.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let points = self.points.iter().zip(&other.points);
+        let (points, moves) = match t {
+            t if t <= 0. => (points.map(|(a, _)| *a).collect(), &self.moves),
+            t if t >= 1. => (points.map(|(_, b)| *b).collect(), &other.moves),
+            t => (
+                points.map(|(a, b)| a.lerp(*b, t)).collect(),
+                if t < 0.5 { &self.moves } else { &other.moves },
+            ),
+        };
+        let segments = self.segments().min(other.segments());
+        Self {
+            points,
+            moves: moves[..segments].to_vec(),
+        }
+    }
+
+    /// Splits segments evenly until there are `segments` of them, without changing the shape.
+    pub fn resample(&self, segments: usize) -> Self {
+        let n = self.segments();
+        if n == 0 || segments <= n {
+            return self.clone();
+        }
+        let mut path = Self {
+            points: vec![self.points[0]],
+            moves: Vec::with_capacity(segments),
+        };
+        for (i, segment) in self.points.windows(3).step_by(2).enumerate() {
+            let pieces = (i + 1) * segments / n - i * segments / n;
+            let (p0, c, p1) = (segment[0], segment[1], segment[2]);
+            let at = |t: f32| p0.lerp(c, t).lerp(c.lerp(p1, t), t);
+            for piece in 0..pieces {
+                let (t0, t1) = (
+                    piece as f32 / pieces as f32,
+                    (piece + 1) as f32 / pieces as f32,
+                );
+                // The control point of a sub-curve is where its end tangents meet
+                let tangent = (c - p0).lerp(p1 - c, t0);
+                path.points.extend([at(t0) + tangent * (t1 - t0), at(t1)]);
+                path.moves.push(self.moves[i]);
+            }
+        }
+        path
+    }
+}
+
+/// Which points are inside a path whose subpaths overlap or intersect themselves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside where a ray crosses the outline an odd number of times, so any overlap is a hole.
+    #[default]
+    EvenOdd,
+    /// Inside where the outline winds around the point, so holes must run the opposite way.
+    /// Used by fonts and SVG.
+    NonZero,
+}
+
+impl From<Vec<Vector3<f32>>> for Path {
+    /// A single subpath.
+    fn from(points: Vec<Vector3<f32>>) -> Self {
+        let mut path = Self::new();
+        path.push_subpath(points);
+        path
+    }
+}
+
+impl Deref for Path {
+    type Target = [Vector3<f32>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.points
+    }
+}
+
+impl DerefMut for Path {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.points
+    }
+}
@@ -1,85 +1,72 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::HashMap};
 
 use wgpu::{CommandEncoder, ComputePipeline, RenderPipeline, ShaderStages};
 
 use super::{
-    utils::pipeline::IntoPass, AnyContext, BindGroupBuilder, PipelineBuilder, Renderable,
-    SurfaceContext,
+    batch::{self, Batch},
+    utils::pipeline::{IntoPass, Render},
+    AnyContext, BindGroupBuilder, FillRule, ObjectId, PipelineBuilder, RenderContext, Renderable,
 };
 
+/// Draws all the objects of a scene in one compute and one render pass.
+///
+/// Each object is drawn with the stencil-then-cover technique: its triangles first mark the
+/// inside in the stencil buffer, then the same triangles color the marked pixels while
+/// clearing them. Strokes are blended where the stencil is clear and cleared again after, so
+/// every object finds a clear stencil buffer.
 pub struct QBezierRenderer {
     compute_pipeline: ComputePipeline,
-    stencil_pipeline: RenderPipeline,
-    render_pipeline: RenderPipeline,
+    setup: PipelineSetup,
+    stencil: StencilPipelines,
+    /// Built the first time an object needs them.
+    draw: HashMap<PipelineKey, DrawPipelines>,
+    batch: Batch,
 }
 
 impl QBezierRenderer {
-    // TODO: This is in shape as well
-    const VERTEX_SIZE: usize = 32;
-
-    pub fn new(ctx: &SurfaceContext<'_>, camera_layout: &wgpu::BindGroupLayout) -> Self {
+    pub fn new(
+        ctx: &dyn RenderContext,
+        camera_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+    ) -> Self {
         let compute_pipeline = Self::make_qbezier_compute_pipeline(ctx);
 
         let shader = ctx
-            .device
+            .device()
             .create_shader_module(wgpu::include_wgsl!("../shader.wgsl"));
-        let vertex_layout = &[wgpu::VertexBufferLayout {
-            array_stride: Self::VERTEX_SIZE as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x2],
-        }];
-
-        let render_layout = BindGroupBuilder::new("QBezier Render Uniform Bind Group layout")
-            .add_uniform_buffer(wgpu::ShaderStages::VERTEX, None)
+        let uniform_layout = BindGroupBuilder::new("Object Uniforms Bind Group layout")
+            .add_dynamic_uniform_buffer(wgpu::ShaderStages::VERTEX_FRAGMENT, None)
             .build(ctx);
-
-        let stencil_pipeline = PipelineBuilder::for_render("Stencil Pipeline", &shader)
-            .vertex(vertex_layout)
-            .fragment("stencil", &[])
-            .depth_stencil(
-                false,
-                wgpu::StencilFaceState {
-                    compare: wgpu::CompareFunction::Always,
-                    fail_op: wgpu::StencilOperation::Keep,
-                    depth_fail_op: wgpu::StencilOperation::Invert,
-                    pass_op: wgpu::StencilOperation::Invert,
-                },
-                1,
-                1,
-            )
-            .add_bind_group_layout(camera_layout)
-            .add_bind_group_layout(&render_layout)
+        let texture_layout = BindGroupBuilder::new("Fill Texture Bind Group layout")
+            .add_texture_float_filterable_d2(wgpu::ShaderStages::FRAGMENT, false)
+            .add_sampler_filterable(wgpu::ShaderStages::FRAGMENT)
             .build(ctx);
 
-        let render_pipeline = PipelineBuilder::for_render("Render Pipeline", &shader)
-            .vertex(vertex_layout)
-            .fragment(
-                "fs_main",
-                &[Some(wgpu::ColorTargetState {
-                    format: ctx.config.view_formats[0],
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            )
-            .depth_stencil(
-                true,
-                wgpu::StencilFaceState {
-                    compare: wgpu::CompareFunction::Equal,
-                    fail_op: wgpu::StencilOperation::Keep,
-                    depth_fail_op: wgpu::StencilOperation::Keep,
-                    pass_op: wgpu::StencilOperation::Keep,
-                },
-                1,
-                1,
-            )
-            .add_bind_group_layout(camera_layout)
-            .add_bind_group_layout(&render_layout)
-            .build(ctx);
+        let stencil = StencilPipelines::new(
+            ctx,
+            &shader,
+            [camera_layout, &uniform_layout, &texture_layout],
+            ctx.format(),
+            sample_count,
+        );
+        // The camera layout is borrowed, the pipelines built later use the one it was built with
+        let setup = PipelineSetup {
+            shader,
+            layouts: [
+                stencil.even_odd.get_bind_group_layout(0),
+                uniform_layout,
+                texture_layout,
+            ],
+            format: ctx.format(),
+            sample_count,
+        };
 
         Self {
             compute_pipeline,
-            stencil_pipeline,
-            render_pipeline,
+            setup,
+            stencil,
+            draw: HashMap::new(),
+            batch: Batch::default(),
         }
     }
 
@@ -87,88 +74,364 @@ impl QBezierRenderer {
         self.compute_pipeline.get_bind_group_layout(0)
     }
 
-    pub fn render_layout(&self) -> wgpu::BindGroupLayout {
-        self.render_pipeline.get_bind_group_layout(1)
-    }
-
+    /// Draws `objects` in order on top of the color view.
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
-        &self,
-        ctx: &SurfaceContext<'_>,
+        &mut self,
+        ctx: &dyn AnyContext,
         color_view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         depth_view: &wgpu::TextureView,
         cam_bind_group: &wgpu::BindGroup,
         encoder: &mut CommandEncoder,
-        object: &RefCell<dyn Renderable + '_>,
-        clear: bool,
+        objects: &[(ObjectId, &RefCell<dyn Renderable + '_>)],
+        depth_mode: DepthMode,
     ) {
-        if object
-            .borrow_mut()
-            .update_compute_buffers(ctx, &self.compute_layout())
+        let [_, uniform_layout, texture_layout] = &self.setup.layouts;
+        for (_, object) in objects {
+            object.borrow_mut().prepare(ctx, texture_layout);
+        }
+        let borrowed = objects.iter().map(|(_, o)| o.borrow()).collect::<Vec<_>>();
+        let objects = objects
+            .iter()
+            .zip(&borrowed)
+            .map(|(&(id, _), object)| (id, &**object))
+            .collect::<Vec<_>>();
+
+        let keys = objects
+            .iter()
+            .map(|(_, object)| PipelineKey {
+                depth_mode,
+                blend_mode: object.blend_mode(),
+                premultiplied: object.premultiplied(),
+            })
+            .collect::<Vec<_>>();
+        for &key in &keys {
+            self.draw
+                .entry(key)
+                .or_insert_with(|| DrawPipelines::new(ctx, &self.setup, key));
+        }
+
+        if self
+            .batch
+            .update_geometry(ctx, &self.compute_layout(), &objects)
         {
+            let fill = self.batch.fill.as_ref().unwrap();
             self.compute_pipeline
                 .begin_pass("Compute Pass")
-                .add_bind_group(&object.borrow().get_compute_object().bind_group)
-                .pass(encoder, (object.borrow().num_compute_workgroups(), 1, 1));
+                .add_bind_group(&fill.bind_group)
+                .pass(encoder, (fill.segments.div_ceil(64), 1, 1));
         }
-        object.borrow_mut().update_render_buffers(ctx);
-
-        let obj = object.borrow();
-        let render_object = obj.get_render_object();
-
-        self.stencil_pipeline
-            .begin_pass("Stencil Pass")
-            .add_bind_group(cam_bind_group)
-            .add_bind_group(&render_object.bind_group)
-            .add_vertex_buffer(&render_object.vertex_buffer)
-            .add_index_buffer(&render_object.index_buffer)
-            .pass(
-                encoder,
-                &[],
-                Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: depth_view,
-                    depth_ops: None,
-                    stencil_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(0),
-                        store: wgpu::StoreOp::Store,
-                    }),
+        self.batch.update_uniforms(ctx, uniform_layout, &objects);
+        let Some(uniforms) = self.batch.uniforms.as_ref().filter(|_| !objects.is_empty()) else {
+            return;
+        };
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
                 }),
-            );
-
-        self.render_pipeline
-            .begin_pass("Render Pass")
-            .add_bind_group(cam_bind_group)
-            .add_bind_group(&render_object.bind_group)
-            .add_vertex_buffer(&render_object.vertex_buffer)
-            .add_index_buffer(&render_object.index_buffer)
-            .set_stencil_reference(1)
-            .pass(
-                encoder,
-                &[Some(wgpu::RenderPassColorAttachment {
-                    view: color_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: if clear {
-                            wgpu::LoadOp::Clear(wgpu::Color::BLACK)
-                        } else {
-                            wgpu::LoadOp::Load
-                        },
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: depth_view,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: wgpu::StoreOp::Discard,
                 }),
-            );
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_bind_group(0, cam_bind_group, &[]);
+
+        let slots = self.batch.slots.iter().zip(&uniforms.offsets);
+        for (((_, object), (slot, &offset)), key) in objects.iter().zip(slots).zip(&keys) {
+            let draw = &self.draw[key];
+            pass.set_bind_group(1, &uniforms.bind_group, &[offset]);
+            pass.set_bind_group(2, object.texture_bind_group(), &[]);
+
+            let fill = self.batch.fill.as_ref();
+            if let Some(fill) = fill.filter(|_| object.has_fill() && !slot.segments.is_empty()) {
+                pass.set_vertex_buffer(0, fill.vertices.slice(..));
+                pass.set_index_buffer(fill.indices.slice(..), wgpu::IndexFormat::Uint32);
+                pass.set_pipeline(match object.fill_rule() {
+                    FillRule::EvenOdd => &self.stencil.even_odd,
+                    FillRule::NonZero => &self.stencil.non_zero,
+                });
+                pass.draw_indexed(slot.fill_indices(), 0, 0..1);
+                pass.set_pipeline(&draw.render);
+                pass.draw_indexed(slot.fill_indices(), 0, 0..1);
+            }
+
+            let stroke = self.batch.stroke.as_ref();
+            if let Some(stroke) = stroke.filter(|_| !slot.stroke_indices.is_empty()) {
+                let base_vertex = slot.stroke_vertices.start as i32;
+                pass.set_vertex_buffer(0, stroke.vertices.slice(..));
+                pass.set_index_buffer(stroke.indices.slice(..), wgpu::IndexFormat::Uint32);
+                pass.set_pipeline(&draw.stroke);
+                pass.draw_indexed(slot.stroke_indices.clone(), base_vertex, 0..1);
+                pass.set_pipeline(&self.stencil.stroke_reset);
+                pass.draw_indexed(slot.stroke_indices.clone(), base_vertex, 0..1);
+            }
+        }
+    }
+}
+
+/// How overlapping objects hide each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DepthMode {
+    /// Objects are drawn in order over each other, as in 2D.
+    #[default]
+    Painter,
+    /// Nearer surfaces hide farther ones whatever the order, as in 3D. Translucent objects
+    /// still blend in draw order.
+    Tested,
+}
+
+/// How the colors of an object combine with what is already drawn below it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// The object is laid over, hiding what is below by its alpha.
+    #[default]
+    Normal,
+    /// Colors are added, brightening what is below, for glows and light.
+    Additive,
+    /// Colors are multiplied, darkening what is below, for shadows and tints. Meant for opaque
+    /// backgrounds, over transparent pixels the result is black.
+    Multiply,
+    /// Inverted colors are multiplied, brightening what is below without saturating, for
+    /// highlights.
+    Screen,
+}
+
+impl BlendMode {
+    /// Blending of the premultiplied colors the shader outputs. Alpha is always composited
+    /// over, so the coverage of the frame stays meaningful.
+    fn blend_state(self) -> wgpu::BlendState {
+        use wgpu::BlendFactor::{Dst, One, OneMinusSrc, OneMinusSrcAlpha};
+        let component = |src_factor, dst_factor| wgpu::BlendComponent {
+            src_factor,
+            dst_factor,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let over = component(One, OneMinusSrcAlpha);
+        wgpu::BlendState {
+            color: match self {
+                BlendMode::Normal => over,
+                BlendMode::Additive => component(One, One),
+                BlendMode::Multiply => component(Dst, OneMinusSrcAlpha),
+                BlendMode::Screen => component(One, OneMinusSrc),
+            },
+            alpha: over,
+        }
+    }
+}
+
+const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+    wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x2];
+const VERTEX_LAYOUT: [wgpu::VertexBufferLayout<'static>; 1] = [wgpu::VertexBufferLayout {
+    array_stride: batch::VERTEX_SIZE as wgpu::BufferAddress,
+    step_mode: wgpu::VertexStepMode::Vertex,
+    attributes: &VERTEX_ATTRIBUTES,
+}];
+
+fn stencil_face(
+    compare: wgpu::CompareFunction,
+    pass_op: wgpu::StencilOperation,
+) -> wgpu::StencilFaceState {
+    wgpu::StencilFaceState {
+        compare,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: pass_op,
+        pass_op,
+    }
+}
+
+/// What the render pipelines have in common.
+struct PipelineSetup {
+    shader: wgpu::ShaderModule,
+    layouts: [wgpu::BindGroupLayout; 3],
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+impl PipelineSetup {
+    /// Only the visible parts are tested against and write depth, the stencil is updated
+    /// wherever the triangles are so it is cleared in the same places.
+    fn builder<'a>(
+        &'a self,
+        label: &'a str,
+        depth_tested: bool,
+        stencil: wgpu::StencilFaceState,
+        read_mask: u32,
+        write_mask: u32,
+    ) -> PipelineBuilder<'a, Render> {
+        PipelineBuilder::for_render(label, &self.shader)
+            .vertex(&VERTEX_LAYOUT)
+            .depth_stencil(depth_tested, stencil, read_mask, write_mask)
+            .depth_compare(match depth_tested {
+                true => wgpu::CompareFunction::LessEqual,
+                false => wgpu::CompareFunction::Always,
+            })
+            .multisample(self.sample_count)
+            .add_bind_group_layout(&self.layouts[0])
+            .add_bind_group_layout(&self.layouts[1])
+            .add_bind_group_layout(&self.layouts[2])
+    }
+}
+
+/// The pipelines only touching the stencil, the same for every object.
+struct StencilPipelines {
+    even_odd: RenderPipeline,
+    non_zero: RenderPipeline,
+    stroke_reset: RenderPipeline,
+}
+
+impl StencilPipelines {
+    fn new(
+        ctx: &dyn RenderContext,
+        shader: &wgpu::ShaderModule,
+        layouts: [&wgpu::BindGroupLayout; 3],
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        // All pipelines share the render pass, so these still have a color target, with writes
+        // disabled
+        let targets = [Some(wgpu::ColorTargetState {
+            format,
+            blend: None,
+            write_mask: wgpu::ColorWrites::empty(),
+        })];
+        let builder = |label, fragment, stencil, read_mask, write_mask| {
+            PipelineBuilder::for_render(label, shader)
+                .vertex(&VERTEX_LAYOUT)
+                .fragment(fragment, &targets)
+                .depth_stencil(false, stencil, read_mask, write_mask)
+                .depth_compare(wgpu::CompareFunction::Always)
+                .multisample(sample_count)
+                .add_bind_group_layout(layouts[0])
+                .add_bind_group_layout(layouts[1])
+                .add_bind_group_layout(layouts[2])
+        };
+
+        // Even-odd flips the lowest bit for every covering triangle. Nonzero counts the
+        // winding, triangles facing away from the camera winding the other way
+        let even_odd = builder(
+            "Even-Odd Stencil Pipeline",
+            "stencil",
+            stencil_face(
+                wgpu::CompareFunction::Always,
+                wgpu::StencilOperation::Invert,
+            ),
+            0xff,
+            1,
+        )
+        .build(ctx);
+        let non_zero = builder(
+            "Nonzero Stencil Pipeline",
+            "stencil",
+            stencil_face(
+                wgpu::CompareFunction::Always,
+                wgpu::StencilOperation::IncrementWrap,
+            ),
+            0xff,
+            0xff,
+        )
+        .stencil_back(stencil_face(
+            wgpu::CompareFunction::Always,
+            wgpu::StencilOperation::DecrementWrap,
+        ))
+        .build(ctx);
+        let stroke_reset = builder(
+            "Stroke Reset Pipeline",
+            "reset",
+            stencil_face(wgpu::CompareFunction::Always, wgpu::StencilOperation::Zero),
+            0xff,
+            0xff,
+        )
+        .build(ctx);
+
+        Self {
+            even_odd,
+            non_zero,
+            stroke_reset,
+        }
+    }
+}
+
+/// Everything the pipelines drawing the colors of an object depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    depth_mode: DepthMode,
+    blend_mode: BlendMode,
+    premultiplied: bool,
+}
+
+/// The pipelines drawing the fill and stroke of objects with the same [`PipelineKey`].
+struct DrawPipelines {
+    render: RenderPipeline,
+    stroke: RenderPipeline,
+}
+
+impl DrawPipelines {
+    fn new(ctx: &dyn AnyContext, setup: &PipelineSetup, key: PipelineKey) -> Self {
+        let targets = [Some(wgpu::ColorTargetState {
+            format: setup.format,
+            blend: Some(key.blend_mode.blend_state()),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let constants =
+            HashMap::from([("premultiplied".to_string(), key.premultiplied as u8 as f64)]);
+        let depth_tested = key.depth_mode == DepthMode::Tested;
+
+        // Inside for either rule wherever the stencil is not zero, which clearing it on the way
+        // also keeps from blending overlapping triangles twice
+        let render = setup
+            .builder(
+                "Render Pipeline",
+                depth_tested,
+                stencil_face(
+                    wgpu::CompareFunction::NotEqual,
+                    wgpu::StencilOperation::Zero,
+                ),
+                0xff,
+                0xff,
+            )
+            .fragment("fs_main", &targets)
+            .constants(&constants)
+            .build(ctx);
+
+        // Each pixel of the outline is blended once, even where its triangles overlap
+        let stroke = setup
+            .builder(
+                "Stroke Pipeline",
+                depth_tested,
+                stencil_face(
+                    wgpu::CompareFunction::Equal,
+                    wgpu::StencilOperation::IncrementClamp,
+                ),
+                1,
+                1,
+            )
+            .fragment("fs_stroke", &targets)
+            .constants(&constants)
+            .build(ctx);
+
+        Self { render, stroke }
     }
 }
 
 impl QBezierRenderer {
-    fn make_qbezier_compute_pipeline(ctx: &impl AnyContext) -> ComputePipeline {
+    fn make_qbezier_compute_pipeline(ctx: &(impl AnyContext + ?Sized)) -> ComputePipeline {
         let shader = ctx
             .device()
             .create_shader_module(wgpu::include_wgsl!("../compute.wgsl"));
@@ -177,6 +440,7 @@ impl QBezierRenderer {
             .add_storage_buffer(ShaderStages::COMPUTE, true, None)
             .add_storage_buffer(ShaderStages::COMPUTE, false, None)
             .add_storage_buffer(ShaderStages::COMPUTE, false, None)
+            .add_storage_buffer(ShaderStages::COMPUTE, true, None)
             .build(ctx);
 
         let cpipeline = PipelineBuilder::for_compute("Compute Pipeline", &shader)
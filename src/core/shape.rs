@@ -4,12 +4,18 @@ use std::{
     rc::Rc,
 };
 
-use cgmath::{ElementWise, Matrix4, One, Quaternion, Vector3, Vector4, VectorSpace, Zero};
-use wgpu::util::DeviceExt;
+use cgmath::{
+    Array, ElementWise, Matrix4, One, Quaternion, Vector2, Vector3, Vector4, VectorSpace, Zero,
+};
 
-// use crate::animations::builder::AnimationBuilder;
+use crate::{animations::builder::AnimationBuilder, texture::Texture};
 
-use super::{utils::latch::Latch, AnyContext, Attach, ObjectUniforms, SurfaceContext};
+use super::{
+    stroke::{self, LineCap, LineJoin, Stroke, StrokeMesh},
+    utils::latch::Latch,
+    AnyContext, Attach, BlendMode, FillRule, Gradient, GradientUniforms, ObjectUniforms, Outline,
+    Paint, Path,
+};
 
 #[derive(Clone)]
 pub struct Transform {
@@ -41,27 +47,25 @@ impl Transform {
 }
 
 pub trait Renderable {
-    // fn as_any_mut(&mut self) -> &mut dyn Any;
-    fn update_render_buffers(&mut self, ctx: &SurfaceContext);
-    fn update_compute_buffers(
-        &mut self,
-        ctx: &SurfaceContext,
-        layout: &wgpu::BindGroupLayout,
-    ) -> bool;
-    fn num_compute_workgroups(&self) -> u32;
-    fn get_render_object(&self) -> &RenderObject;
-    fn get_compute_object(&self) -> &ComputeObject;
-}
-
-pub struct RenderObject {
-    pub vertex_buffer: wgpu::Buffer,
-    pub index_buffer: wgpu::Buffer,
-    uniform_buffer: wgpu::Buffer,
-    pub bind_group: wgpu::BindGroup,
-}
-pub struct ComputeObject {
-    buffer: wgpu::Buffer,
-    pub bind_group: wgpu::BindGroup,
+    /// Brings the points, stroke mesh, uniforms and texture up to date with the shape.
+    fn prepare(&mut self, ctx: &dyn AnyContext, texture_layout: &wgpu::BindGroupLayout);
+    /// Changes whenever [`Self::prepare`] recalculates the points or the stroke mesh.
+    fn revision(&self) -> u64;
+    fn points(&self) -> &Path;
+    fn stroke_mesh(&self) -> &StrokeMesh;
+    fn uniforms(&self) -> &ObjectUniforms;
+    /// Fill texture and its sampler, only available once prepared.
+    fn texture_bind_group(&self) -> &wgpu::BindGroup;
+    fn has_fill(&self) -> bool;
+    fn fill_rule(&self) -> FillRule;
+    fn z_index(&self) -> i32;
+    fn blend_mode(&self) -> BlendMode;
+    /// Whether the colors and texture already have their alpha multiplied in.
+    fn premultiplied(&self) -> bool;
+    /// Places the points in the world.
+    fn model(&self) -> Matrix4<f32>;
+    /// Snapshot of the object as it will be drawn next, without touching the GPU.
+    fn outline(&self) -> Outline;
 }
 
 pub struct Mobject<T: HasPoints> {
@@ -77,11 +81,11 @@ impl<T: Clone + HasPoints> Clone for Mobject<T> {
     }
 }
 
-// impl<T: Clone + HasPoints> Mobject<T> {
-//     pub fn animate(&self) -> AnimationBuilder<T> {
-//         // AnimationBuilder::new(self.ref_clone())
-//     }
-// }
+impl<T: Clone + HasPoints> Mobject<T> {
+    pub fn animate(&self) -> AnimationBuilder<T> {
+        AnimationBuilder::new(self.ref_clone(), AnimationBuilder::<T>::DEFAULT_DURATION)
+    }
+}
 
 impl<T: HasPoints> Deref for Mobject<T> {
     type Target = Rc<RefCell<Shape<T>>>;
@@ -127,25 +131,112 @@ impl<T: HasPoints> Mobject<T> {
         self
     }
 
+    /// Fills the shape with a solid color, replacing any gradient.
     pub fn color(&self, color: impl Into<Vector4<f32>>) -> &Self {
-        self.borrow_mut().uniforms.color = color.into();
+        let mut shape = self.borrow_mut();
+        shape.uniforms.color = color.into();
+        shape.uniforms.gradient = GradientUniforms::solid();
+        self
+    }
+
+    pub fn gradient(&self, gradient: Gradient) -> &Self {
+        self.borrow_mut().uniforms.gradient = gradient.into();
         self
     }
 
-    // pub fn animate(&self) -> AnimationBuilder;
+    /// Outlines the shape, a width of zero removes the outline. The width is in the shape's own
+    /// units and scales with it.
+    pub fn stroke(&self, width: f32, color: impl Into<Vector4<f32>>) -> &Self {
+        let mut shape = self.borrow_mut();
+        shape.stroke.width = width.max(0.);
+        shape.uniforms.stroke_color = color.into();
+        self
+    }
+
+    pub fn stroke_style(&self, cap: LineCap, join: LineJoin) -> &Self {
+        let mut shape = self.borrow_mut();
+        shape.stroke.cap = cap;
+        shape.stroke.join = join;
+        self
+    }
+
+    /// Fills the shape with an image stretched over its bounding box, tinted by its color.
+    pub fn texture(&self, image: impl Into<Rc<image::DynamicImage>>) -> &Self {
+        *self.borrow_mut().texture = Some(image.into());
+        self
+    }
+
+    /// Whether the inside of the shape is drawn, disable for outline-only shapes.
+    pub fn fill(&self, fill: bool) -> &Self {
+        self.borrow_mut().fill = fill;
+        self
+    }
+
+    /// Layer of the shape, shapes with a higher index are drawn on top of lower ones and shapes
+    /// on the same layer in the order they were added. Defaults to 0.
+    pub fn z_index(&self, z_index: i32) -> &Self {
+        self.borrow_mut().z_index = z_index;
+        self
+    }
+
+    /// How overlapping subpaths are filled, even-odd by default.
+    pub fn fill_rule(&self, fill_rule: FillRule) -> &Self {
+        self.borrow_mut().fill_rule = fill_rule;
+        self
+    }
+
+    /// Opacity of the fill and the stroke, multiplied with the alpha of their colors. Fully
+    /// opaque by default.
+    pub fn opacity(&self, opacity: f32) -> &Self {
+        self.borrow_mut().set_opacity(opacity, opacity);
+        self
+    }
+
+    pub fn fill_opacity(&self, opacity: f32) -> &Self {
+        let stroke_opacity = self.borrow().stroke_opacity();
+        self.borrow_mut().set_opacity(opacity, stroke_opacity);
+        self
+    }
+
+    pub fn stroke_opacity(&self, opacity: f32) -> &Self {
+        let fill_opacity = self.borrow().fill_opacity();
+        self.borrow_mut().set_opacity(fill_opacity, opacity);
+        self
+    }
+
+    /// How the shape combines with what is drawn below it, normal by default.
+    pub fn blend_mode(&self, blend_mode: BlendMode) -> &Self {
+        self.borrow_mut().blend_mode = blend_mode;
+        self
+    }
+
+    /// Takes the colors and texture of the shape as premultiplied by their alpha, as is common
+    /// for images exported by compositing tools. Off by default.
+    pub fn premultiplied(&self, premultiplied: bool) -> &Self {
+        self.borrow_mut().premultiplied = premultiplied;
+        self
+    }
 }
 
 pub trait HasPoints {
-    fn calc_points(&self) -> Vec<Vector3<f32>>;
+    fn calc_points(&self) -> Path;
 }
 
 pub struct Shape<T: HasPoints> {
     shape: Latch<T>,
     transform: Latch<Transform>,
-    pub points: Latch<Vec<Vector3<f32>>>,
-    uniforms: Latch<ObjectUniforms>,
-    render_object: Option<RenderObject>,
-    compute_object: Option<ComputeObject>,
+    pub points: Latch<Path>,
+    uniforms: ObjectUniforms,
+    stroke: Latch<Stroke>,
+    fill: bool,
+    fill_rule: FillRule,
+    z_index: i32,
+    blend_mode: BlendMode,
+    premultiplied: bool,
+    texture: Latch<Option<Rc<image::DynamicImage>>>,
+    stroke_mesh: StrokeMesh,
+    texture_bind_group: Option<wgpu::BindGroup>,
+    revision: u64,
 }
 
 impl<T> Clone for Shape<T>
@@ -158,8 +249,16 @@ where
             transform: self.transform.clone(),
             points: self.points.clone(),
             uniforms: self.uniforms.clone(),
-            render_object: None,
-            compute_object: None,
+            stroke: self.stroke.clone(),
+            fill: self.fill,
+            fill_rule: self.fill_rule,
+            z_index: self.z_index,
+            blend_mode: self.blend_mode,
+            premultiplied: self.premultiplied,
+            texture: self.texture.clone(),
+            stroke_mesh: self.stroke_mesh.clone(),
+            texture_bind_group: None,
+            revision: self.revision,
         }
     }
 }
@@ -182,171 +281,216 @@ impl<T: HasPoints> Shape<T> {
     pub fn new(shape: T) -> Self {
         let transform = Latch::new_reset(Transform::new());
         let color = Vector4::new(1.0, 1.0, 1.0, 1.0);
-        let uniforms = Latch::new_reset(ObjectUniforms::new(&transform, color));
+        let uniforms = ObjectUniforms::new(&transform, color);
         Self {
             transform,
             uniforms,
             shape: Latch::new_set(shape),
-            points: Latch::new_reset(Vec::new()),
-            render_object: None,
-            compute_object: None,
+            points: Latch::new_reset(Path::new()),
+            stroke: Latch::new_reset(Stroke::default()),
+            fill: true,
+            fill_rule: FillRule::default(),
+            z_index: 0,
+            blend_mode: BlendMode::default(),
+            premultiplied: false,
+            texture: Latch::new_reset(None),
+            stroke_mesh: StrokeMesh::default(),
+            texture_bind_group: None,
+            revision: 0,
         }
     }
 
+    pub fn fill_opacity(&self) -> f32 {
+        self.uniforms.fill_opacity
+    }
+
+    pub fn stroke_opacity(&self) -> f32 {
+        self.uniforms.stroke_opacity
+    }
+
+    pub fn set_opacity(&mut self, fill: f32, stroke: f32) {
+        self.uniforms.fill_opacity = fill.clamp(0., 1.);
+        self.uniforms.stroke_opacity = stroke.clamp(0., 1.);
+    }
+
+    /// Places the shape between two others, keeping its own points.
+    pub fn interpolate_transform<U: HasPoints, V: HasPoints>(
+        &mut self,
+        a: &Shape<U>,
+        b: &Shape<V>,
+        t: f32,
+    ) {
+        *self.transform = a.transform.lerp(&b.transform, t);
+    }
+
     pub fn interpolate<U: HasPoints, V: HasPoints>(&mut self, a: &Shape<U>, b: &Shape<V>, t: f32) {
-        *self.points = a
-            .points
-            .iter()
-            .zip(b.points.iter())
-            .map(|(a, b)| a.lerp(*b, t))
-            .collect();
+        *self.points = a.points.lerp(&b.points, t);
         *self.transform = a.transform.lerp(&b.transform, t);
         self.uniforms.color = a.uniforms.color.lerp(b.uniforms.color, t);
+        self.uniforms.gradient = GradientUniforms::lerp(
+            (&a.uniforms.gradient, a.uniforms.color),
+            (&b.uniforms.gradient, b.uniforms.color),
+            t,
+        );
+        self.uniforms.stroke_color = a.uniforms.stroke_color.lerp(b.uniforms.stroke_color, t);
+        self.set_opacity(
+            a.fill_opacity() + (b.fill_opacity() - a.fill_opacity()) * t,
+            a.stroke_opacity() + (b.stroke_opacity() - a.stroke_opacity()) * t,
+        );
+        self.stroke.width = a.stroke.width + (b.stroke.width - a.stroke.width) * t;
     }
 }
 
 impl<T: HasPoints> Renderable for Shape<T> {
-    fn get_render_object(&self) -> &RenderObject {
-        self.render_object.as_ref().unwrap()
-    }
-
-    fn get_compute_object(&self) -> &ComputeObject {
-        self.compute_object.as_ref().unwrap()
-    }
-
-    fn update_render_buffers(&mut self, ctx: &SurfaceContext) {
+    fn prepare(&mut self, ctx: &dyn AnyContext, texture_layout: &wgpu::BindGroupLayout) {
+        let mut changed = match (self.shape.reset(), self.points.reset()) {
+            (false, false) => false,
+            (true, false) => {
+                *self.points = self.shape.calc_points();
+                self.points.reset();
+                true
+            }
+            _ => true,
+        };
+        if changed {
+            self.update_bounds();
+        }
+        if self.stroke.reset() || changed {
+            self.stroke_mesh = stroke::tessellate(&self.points, &self.stroke);
+            changed = true;
+        }
+        if changed {
+            self.revision += 1;
+        }
         if self.transform.reset() {
             self.uniforms.model = self.transform.get_matrix();
         }
-        if self.uniforms.reset() {
-            let mut buff = encase::UniformBuffer::new(Vec::<u8>::new());
-            buff.write(self.uniforms.deref()).unwrap();
-            let buff = buff.into_inner();
-            ctx.queue().write_buffer(
-                &self.render_object.as_ref().unwrap().uniform_buffer,
-                0,
-                &buff,
-            );
+        if self.texture.reset() || self.texture_bind_group.is_none() {
+            self.uniforms.textured = self.texture.is_some() as u32;
+            let texture = self.create_texture(ctx);
+            self.texture_bind_group = Some(texture_layout.attach(
+                ctx,
+                "Fill Texture Bind Group",
+                vec![
+                    wgpu::BindingResource::TextureView(&texture.view),
+                    wgpu::BindingResource::Sampler(&texture.sampler),
+                ],
+            ));
         }
     }
 
-    fn update_compute_buffers(
-        &mut self,
-        ctx: &SurfaceContext,
-        layout: &wgpu::BindGroupLayout,
-    ) -> bool {
-        match (self.shape.reset(), self.points.reset()) {
-            (false, false) => return false,
-            (true, false) => *self.points = self.shape.calc_points(),
-            _ => (),
-        }
+    fn revision(&self) -> u64 {
+        self.revision
+    }
 
-        let mut data = encase::StorageBuffer::new(Vec::new());
-        data.write(self.points.deref()).unwrap();
-        let data: Vec<u8> = data.into_inner();
+    fn points(&self) -> &Path {
+        &self.points
+    }
+
+    fn stroke_mesh(&self) -> &StrokeMesh {
+        &self.stroke_mesh
+    }
 
-        if self
-            .compute_object
+    fn uniforms(&self) -> &ObjectUniforms {
+        &self.uniforms
+    }
+
+    fn texture_bind_group(&self) -> &wgpu::BindGroup {
+        self.texture_bind_group
             .as_ref()
-            .is_some_and(|ob| (data.len() / 2..=data.len()).contains(&(ob.buffer.size() as usize)))
-        {
-            ctx.queue()
-                .write_buffer(&self.compute_object.as_ref().unwrap().buffer, 0, &data);
-            return true;
-        }
+            .expect("Shape is not prepared")
+    }
 
-        let vertex_buffer = self.create_vertex_buffer(ctx);
-        let index_buffer = self.create_index_buffer(ctx);
-
-        let buffer = ctx
-            .device()
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Point Buffer"),
-                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-                contents: &data,
-            });
-
-        let bind_group = layout.attach(
-            ctx,
-            "Compute Bind Group",
-            vec![
-                buffer.as_entire_binding(),
-                vertex_buffer.as_entire_binding(),
-                index_buffer.as_entire_binding(),
-            ],
-        );
+    fn has_fill(&self) -> bool {
+        self.fill
+    }
 
-        let render_object = self.render_object.as_mut().unwrap();
-        render_object.vertex_buffer = vertex_buffer;
-        render_object.index_buffer = index_buffer;
-        self.compute_object = Some(ComputeObject { bind_group, buffer });
+    fn fill_rule(&self) -> FillRule {
+        self.fill_rule
+    }
 
-        true
+    fn z_index(&self) -> i32 {
+        self.z_index
     }
 
-    fn num_compute_workgroups(&self) -> u32 {
-        (((self.points.len() / 2) as f32) / 64.0).ceil() as u32
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn premultiplied(&self) -> bool {
+        self.premultiplied
+    }
+
+    fn model(&self) -> Matrix4<f32> {
+        self.transform.get_matrix()
+    }
+
+    fn outline(&self) -> Outline {
+        // Outlines take straight colors, with the opacity in their alpha
+        let straight = |color: Vector4<f32>, opacity: f32| {
+            let color = match self.premultiplied && color.w > 0. {
+                true => (color.truncate() / color.w).extend(color.w),
+                false => color,
+            };
+            color.truncate().extend(color.w * opacity)
+        };
+        let fill_opacity = self.fill_opacity();
+        Outline {
+            // Points are only recalculated when the next frame is rendered
+            points: if self.shape.is_set() {
+                self.shape.calc_points()
+            } else {
+                Path::clone(&self.points)
+            },
+            model: self.model(),
+            fill: self
+                .fill
+                .then(|| match self.uniforms.gradient.to_gradient() {
+                    Some(mut gradient) => {
+                        for (_, color) in gradient.stops.iter_mut() {
+                            *color = straight(*color, fill_opacity);
+                        }
+                        Paint::Gradient(gradient)
+                    }
+                    None => Paint::Color(straight(self.uniforms.color, fill_opacity)),
+                }),
+            fill_rule: self.fill_rule,
+            blend_mode: self.blend_mode,
+            stroke: (self.stroke.width > 0.).then(|| {
+                (
+                    self.stroke.deref().clone(),
+                    straight(self.uniforms.stroke_color, self.stroke_opacity()),
+                )
+            }),
+        }
     }
 }
 
 impl<T: HasPoints> Shape<T> {
-    const VERTEX_SIZE: usize = 32;
-
-    fn create_vertex_buffer(&self, ctx: &SurfaceContext) -> wgpu::Buffer {
-        ctx.device().create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Vertex Buffer"),
-            size: (self.points.len() as u64 / 2 * 3 + 1) * Self::VERTEX_SIZE as wgpu::BufferAddress,
-            usage: wgpu::BufferUsages::VERTEX
-                | wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        })
-    }
-
-    fn create_index_buffer(&self, ctx: &SurfaceContext) -> wgpu::Buffer {
-        ctx.device().create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Index Buffer"),
-            size: (self.points.len() as u64 / 2 * 6)
-                * std::mem::size_of::<u32>() as wgpu::BufferAddress,
-            usage: wgpu::BufferUsages::INDEX
-                | wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        })
-    }
-
-    pub fn create_render_object(&mut self, ctx: &SurfaceContext, layout: wgpu::BindGroupLayout) {
-        *self.points = self.shape.calc_points();
-        self.points.reset();
-        if self.transform.reset() {
-            self.uniforms.model = self.transform.get_matrix();
-        }
-        let index_buffer = self.create_index_buffer(ctx);
-        let vertex_buffer = self.create_vertex_buffer(ctx);
-
-        let mut buff = encase::UniformBuffer::new(Vec::<u8>::new());
-        buff.write(self.uniforms.deref()).unwrap();
-        let buff = buff.into_inner();
-
-        let uniform_buffer = ctx
-            .device()
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("QBezier Uniform Buffer"),
-                contents: bytemuck::cast_slice(&buff),
-                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            });
-
-        let bind_group = layout.attach(
-            ctx,
-            "QBezier Bind Group",
-            vec![uniform_buffer.as_entire_binding()],
+    fn update_bounds(&mut self) {
+        let (min, max) = self.points.iter().fold(
+            (
+                Vector2::from_value(f32::INFINITY),
+                Vector2::from_value(f32::NEG_INFINITY),
+            ),
+            |(min, max), p| {
+                (
+                    Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
         );
+        self.uniforms.bounds = Vector4::new(min.x, min.y, max.x, max.y);
+    }
 
-        self.render_object = Some(RenderObject {
-            vertex_buffer,
-            index_buffer,
-            uniform_buffer,
-            bind_group,
-        });
+    /// Creates the texture sampled by the fill, a white pixel for untextured shapes.
+    fn create_texture(&self, ctx: &(impl AnyContext + ?Sized)) -> Texture {
+        let white = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            1,
+            1,
+            image::Rgba([255; 4]),
+        ));
+        let image = self.texture.as_deref().unwrap_or(&white);
+        Texture::from_image(ctx.device(), ctx.queue(), image, Some("Fill Texture"))
     }
 }
@@ -0,0 +1,229 @@
+use std::f32::consts::PI;
+
+use cgmath::{InnerSpace, Vector2, Vector3};
+
+use super::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Outline style of a shape. The width is in the shape's own units, so it scales with the shape
+/// like its points do. A width of zero disables the stroke.
+#[derive(Debug, Clone)]
+pub struct Stroke {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Miter joins longer than this many half widths fall back to bevels.
+    pub miter_limit: f32,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self {
+            width: 0.,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: 4.,
+        }
+    }
+}
+
+// Same layout as `Vertex` in compute.wgsl, so both pipelines share a vertex layout
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct StrokeVertex {
+    position: [f32; 3],
+    _pad: f32,
+    uv: [f32; 2],
+    _pad2: [f32; 2],
+}
+
+impl StrokeVertex {
+    pub fn position(&self) -> Vector3<f32> {
+        self.position.into()
+    }
+}
+
+/// Triangles covering an outline, in the same coordinates as the points it was built from.
+#[derive(Debug, Clone, Default)]
+pub struct StrokeMesh {
+    pub vertices: Vec<StrokeVertex>,
+    pub indices: Vec<u32>,
+}
+
+/// Maximum distance between a curve and its flattened polyline, in the shape's units.
+const TOLERANCE: f32 = 0.001;
+const EPSILON: f32 = 1e-6;
+/// End points closer than this close the path, so rounding errors in e.g. circles don't add caps.
+const CLOSE_EPSILON: f32 = 1e-4;
+/// Angle covered by one triangle of round joins and caps.
+const ROUND_STEP: f32 = PI / 16.;
+
+fn perp(d: Vector2<f32>) -> Vector2<f32> {
+    Vector2::new(-d.y, d.x)
+}
+
+fn direction(a: Vector3<f32>, b: Vector3<f32>) -> Vector2<f32> {
+    (b - a).truncate().normalize()
+}
+
+fn offset(p: Vector3<f32>, by: Vector2<f32>) -> Vector3<f32> {
+    p + by.extend(0.)
+}
+
+/// Turns the quadratic segments `p0, c, p1, c, p2, ..` into a polyline.
+fn flatten(points: &[Vector3<f32>]) -> Vec<Vector3<f32>> {
+    let mut line = points.first().into_iter().copied().collect::<Vec<_>>();
+    for segment in points.windows(3).step_by(2) {
+        let (p0, c, p1) = (segment[0], segment[1], segment[2]);
+        let deviation = (p0 - c * 2. + p1).magnitude();
+        let steps = (deviation / (8. * TOLERANCE)).sqrt().ceil().clamp(1., 64.) as usize;
+        line.extend((1..=steps).map(|i| {
+            let t = i as f32 / steps as f32;
+            p0 * (1. - t) * (1. - t) + c * 2. * t * (1. - t) + p1 * t * t
+        }));
+    }
+    line.dedup_by(|a, b| (*a - *b).truncate().magnitude2() < EPSILON * EPSILON);
+    line
+}
+
+impl StrokeMesh {
+    fn triangle(&mut self, a: Vector3<f32>, b: Vector3<f32>, c: Vector3<f32>) {
+        for p in [a, b, c] {
+            self.indices.push(self.vertices.len() as u32);
+            self.vertices.push(StrokeVertex {
+                position: p.into(),
+                _pad: 0.,
+                uv: [0., 0.],
+                _pad2: [0., 0.],
+            });
+        }
+    }
+
+    fn quad(&mut self, a: Vector3<f32>, b: Vector3<f32>, c: Vector3<f32>, d: Vector3<f32>) {
+        self.triangle(a, b, c);
+        self.triangle(a, c, d);
+    }
+
+    /// Fan around `center` starting at offset `from` and turning by `sweep` radians.
+    fn fan(&mut self, center: Vector3<f32>, from: Vector2<f32>, sweep: f32) {
+        let steps = (sweep.abs() / ROUND_STEP).ceil().max(1.) as usize;
+        let start = from.y.atan2(from.x);
+        let radius = from.magnitude();
+        let point = |i: usize| {
+            let angle = start + sweep * i as f32 / steps as f32;
+            offset(center, Vector2::new(angle.cos(), angle.sin()) * radius)
+        };
+        for i in 0..steps {
+            self.triangle(center, point(i), point(i + 1));
+        }
+    }
+
+    fn join(&mut self, prev: Vector3<f32>, v: Vector3<f32>, next: Vector3<f32>, stroke: &Stroke) {
+        let (d0, d1) = (direction(prev, v), direction(v, next));
+        let cross = d0.perp_dot(d1);
+        if cross.abs() < EPSILON && d0.dot(d1) > 0. {
+            return;
+        }
+        // Only the outer side of the turn needs filling, the segments overlap on the inner one
+        let half = stroke.width / 2.;
+        let side = if cross > 0. { -half } else { half };
+        let (n0, n1) = (perp(d0) * side, perp(d1) * side);
+
+        let miter = (n0 + n1).normalize();
+        let cos = miter.dot(n0) / half;
+        match stroke.join {
+            LineJoin::Round => self.fan(v, n0, n0.perp_dot(n1).atan2(n0.dot(n1))),
+            LineJoin::Miter if cos > EPSILON && 1. / cos <= stroke.miter_limit => {
+                let tip = offset(v, miter * half / cos);
+                self.quad(v, offset(v, n0), tip, offset(v, n1));
+            }
+            _ => self.triangle(v, offset(v, n0), offset(v, n1)),
+        }
+    }
+
+    /// Cap at the end point `p` of a line leaving it in direction `outward`.
+    fn cap(&mut self, p: Vector3<f32>, outward: Vector2<f32>, stroke: &Stroke) {
+        let half = stroke.width / 2.;
+        let n = perp(outward) * half;
+        match stroke.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let e = outward * half;
+                self.quad(
+                    offset(p, n),
+                    offset(p, n + e),
+                    offset(p, e - n),
+                    offset(p, -n),
+                );
+            }
+            LineCap::Round => self.fan(p, n, -PI),
+        }
+    }
+}
+
+/// Builds the triangles outlining every subpath of `path`.
+///
+/// Subpaths are treated as closed if their first and last points coincide.
+pub fn tessellate(path: &Path, stroke: &Stroke) -> StrokeMesh {
+    let mut mesh = StrokeMesh::default();
+    if stroke.width > 0. {
+        for subpath in path.subpaths() {
+            tessellate_subpath(&mut mesh, subpath, stroke);
+        }
+    }
+    mesh
+}
+
+fn tessellate_subpath(mesh: &mut StrokeMesh, points: &[Vector3<f32>], stroke: &Stroke) {
+    let mut line = flatten(points);
+    let half = stroke.width / 2.;
+    if line.len() < 2 {
+        return;
+    }
+
+    let closed = (line[0] - line[line.len() - 1]).magnitude2() < CLOSE_EPSILON * CLOSE_EPSILON;
+    if closed {
+        line.pop();
+    }
+    let n = line.len();
+    if n < 2 {
+        return;
+    }
+
+    let segments = if closed { n } else { n - 1 };
+    for i in 0..segments {
+        let (a, b) = (line[i], line[(i + 1) % n]);
+        let normal = perp(direction(a, b)) * half;
+        mesh.quad(
+            offset(a, normal),
+            offset(b, normal),
+            offset(b, -normal),
+            offset(a, -normal),
+        );
+    }
+
+    let joins = if closed { 0..n } else { 1..n - 1 };
+    for i in joins {
+        mesh.join(line[(i + n - 1) % n], line[i], line[(i + 1) % n], stroke);
+    }
+
+    if !closed {
+        mesh.cap(line[0], direction(line[1], line[0]), stroke);
+        mesh.cap(line[n - 1], direction(line[n - 2], line[n - 1]), stroke);
+    }
+}
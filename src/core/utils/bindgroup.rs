@@ -54,6 +54,25 @@ impl BindGroupBuilder {
         self
     }
 
+    /// A uniform buffer holding several values, bound at an offset given with each draw.
+    pub fn add_dynamic_uniform_buffer(
+        mut self,
+        visibility: wgpu::ShaderStages,
+        min_binding_size: Option<BufferSize>,
+    ) -> Self {
+        self.entries.push(wgpu::BindGroupLayoutEntry {
+            binding: self.entries.len() as u32,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size,
+            },
+            count: None,
+        });
+        self
+    }
+
     pub fn add_sampler(
         mut self,
         visibility: wgpu::ShaderStages,
@@ -105,7 +124,7 @@ impl BindGroupBuilder {
         )
     }
 
-    pub fn build(self, ctx: &impl AnyContext) -> wgpu::BindGroupLayout {
+    pub fn build(self, ctx: &(impl AnyContext + ?Sized)) -> wgpu::BindGroupLayout {
         ctx.device()
             .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: self.label.as_deref(),
@@ -117,7 +136,7 @@ impl BindGroupBuilder {
 pub trait Attach {
     fn attach(
         &self,
-        ctx: &impl AnyContext,
+        ctx: &(impl AnyContext + ?Sized),
         label: impl Into<String>,
         entries: Vec<wgpu::BindingResource<'_>>,
     ) -> wgpu::BindGroup;
@@ -126,7 +145,7 @@ pub trait Attach {
 impl Attach for wgpu::BindGroupLayout {
     fn attach(
         &self,
-        ctx: &impl AnyContext,
+        ctx: &(impl AnyContext + ?Sized),
         label: impl Into<String>,
         entries: Vec<wgpu::BindingResource<'_>>,
     ) -> wgpu::BindGroup {
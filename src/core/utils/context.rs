@@ -1,10 +1,20 @@
 use winit::dpi::PhysicalSize;
 
+use crate::texture::Texture;
+
 pub trait AnyContext {
     fn device(&self) -> &wgpu::Device;
     fn queue(&self) -> &wgpu::Queue;
 }
 
+/// A context that owns something to draw into, either a window surface or an offscreen texture.
+pub trait RenderContext: AnyContext {
+    fn format(&self) -> wgpu::TextureFormat;
+    fn size(&self) -> (u32, u32);
+    fn resize(&mut self, size: PhysicalSize<u32>);
+    fn get_current_frame(&self) -> Result<Frame, wgpu::SurfaceError>;
+}
+
 macro_rules! impl_context {
     ($type:ty) => {
         impl AnyContext for $type {
@@ -20,6 +30,7 @@ macro_rules! impl_context {
 
 impl_context!(Context);
 impl_context!(SurfaceContext<'_>);
+impl_context!(TextureContext);
 
 pub struct Context {
     instance: wgpu::Instance,
@@ -82,6 +93,34 @@ impl Context {
             config,
         }
     }
+
+    pub fn attach_texture(self, width: u32, height: u32) -> TextureContext {
+        let texture = Texture::create_render_target(
+            &self.device,
+            (width, height),
+            TextureContext::FORMAT,
+            "Offscreen Texture",
+        );
+        TextureContext {
+            device: self.device,
+            queue: self.queue,
+            texture,
+        }
+    }
+}
+
+/// An owned frame to render into, presented to the surface (if any) when done.
+pub struct Frame {
+    surface_texture: Option<wgpu::SurfaceTexture>,
+    pub view: wgpu::TextureView,
+}
+
+impl Frame {
+    pub fn present(self) {
+        if let Some(surface_texture) = self.surface_texture {
+            surface_texture.present();
+        }
+    }
 }
 
 pub struct SurfaceContext<'a> {
@@ -91,12 +130,77 @@ pub struct SurfaceContext<'a> {
     pub config: wgpu::SurfaceConfiguration,
 }
 
-impl<'a> SurfaceContext<'a> {
-    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+impl RenderContext for SurfaceContext<'_> {
+    fn format(&self) -> wgpu::TextureFormat {
+        self.config.view_formats[0]
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    fn resize(&mut self, size: PhysicalSize<u32>) {
         log::info!("Surface resize {size:?}");
 
         self.config.width = size.width.max(1);
         self.config.height = size.height.max(1);
         self.surface.configure(&self.device, &self.config);
     }
+
+    fn get_current_frame(&self) -> Result<Frame, wgpu::SurfaceError> {
+        let surface_texture = self.surface.get_current_texture()?;
+        let view = surface_texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor {
+                format: Some(self.format()),
+                ..Default::default()
+            });
+        Ok(Frame {
+            surface_texture: Some(surface_texture),
+            view,
+        })
+    }
+}
+
+/// Offscreen context that renders into a texture instead of a window surface.
+pub struct TextureContext {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub texture: Texture,
+}
+
+impl TextureContext {
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+}
+
+impl RenderContext for TextureContext {
+    fn format(&self) -> wgpu::TextureFormat {
+        self.texture.texture.format()
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.texture.texture.width(), self.texture.texture.height())
+    }
+
+    fn resize(&mut self, size: PhysicalSize<u32>) {
+        log::info!("Texture resize {size:?}");
+
+        self.texture = Texture::create_render_target(
+            &self.device,
+            (size.width, size.height),
+            self.format(),
+            "Offscreen Texture",
+        );
+    }
+
+    fn get_current_frame(&self) -> Result<Frame, wgpu::SurfaceError> {
+        let view = self
+            .texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        Ok(Frame {
+            surface_texture: None,
+            view,
+        })
+    }
 }
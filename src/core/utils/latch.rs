@@ -32,6 +32,11 @@ impl<T> Latch<T> {
         Self { value, latch: true }
     }
 
+    /// Whether the value changed since the last `reset`.
+    pub fn is_set(&self) -> bool {
+        self.latch
+    }
+
     pub fn reset(&mut self) -> bool {
         if self.latch {
             self.latch = false;
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use wgpu::{
     ColorTargetState, ComputePipeline, DepthStencilState, FragmentState, RenderPipeline,
     StencilFaceState, VertexBufferLayout,
@@ -5,7 +7,7 @@ use wgpu::{
 
 use super::context::AnyContext;
 
-trait PipelineType {}
+pub trait PipelineType {}
 pub struct RenderNoVertex;
 pub struct Render;
 pub struct Compute;
@@ -19,6 +21,7 @@ pub struct PipelineBuilder<'a, T: PipelineType> {
     vertex: Option<&'a [VertexBufferLayout<'a>]>,
     fragment: Option<FragmentState<'a>>,
     depth_stencil: Option<DepthStencilState>,
+    sample_count: u32,
     bind_group_layouts: Vec<&'a wgpu::BindGroupLayout>,
     marker: std::marker::PhantomData<T>,
 }
@@ -31,12 +34,13 @@ impl<'a, T: PipelineType> PipelineBuilder<'a, T> {
             fragment: None,
             vertex: None,
             depth_stencil: None,
+            sample_count: 1,
             bind_group_layouts: Vec::new(),
             marker: std::marker::PhantomData,
         }
     }
 
-    fn pipeline_layout(&self, ctx: &impl AnyContext) -> wgpu::PipelineLayout {
+    fn pipeline_layout(&self, ctx: &(impl AnyContext + ?Sized)) -> wgpu::PipelineLayout {
         let label = self.label.to_string() + " layout";
         ctx.device()
             .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -60,7 +64,7 @@ impl<'a> PipelineBuilder<'a, Compute> {
         Self::new(label, shader)
     }
 
-    pub fn build(self, ctx: &'a impl AnyContext) -> ComputePipeline {
+    pub fn build(self, ctx: &'a (impl AnyContext + ?Sized)) -> ComputePipeline {
         let pipeline = ctx
             .device()
             .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
@@ -90,6 +94,7 @@ impl<'a> PipelineBuilder<'a, RenderNoVertex> {
             vertex: Some(vertex_buffers),
             fragment: self.fragment,
             depth_stencil: self.depth_stencil,
+            sample_count: self.sample_count,
             bind_group_layouts: self.bind_group_layouts,
             marker: std::marker::PhantomData,
         }
@@ -133,7 +138,37 @@ impl<'a> PipelineBuilder<'a, Render> {
         self
     }
 
-    pub fn build(self, ctx: &'a impl AnyContext) -> RenderPipeline {
+    /// Values of the shader's pipeline-overridable constants, must be called after
+    /// [`Self::fragment`].
+    pub fn constants<'b: 'a>(mut self, constants: &'b HashMap<String, f64>) -> Self {
+        if let Some(fragment) = self.fragment.as_mut() {
+            fragment.compilation_options.constants = constants;
+        }
+        self
+    }
+
+    /// Must be called after [`Self::depth_stencil`], which uses the same state for both faces.
+    pub fn stencil_back(mut self, stencil: StencilFaceState) -> Self {
+        if let Some(depth_stencil) = self.depth_stencil.as_mut() {
+            depth_stencil.stencil.back = stencil;
+        }
+        self
+    }
+
+    /// Must be called after [`Self::depth_stencil`], which defaults to `Less`.
+    pub fn depth_compare(mut self, compare: wgpu::CompareFunction) -> Self {
+        if let Some(depth_stencil) = self.depth_stencil.as_mut() {
+            depth_stencil.depth_compare = compare;
+        }
+        self
+    }
+
+    pub fn multisample(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    pub fn build(self, ctx: &'a (impl AnyContext + ?Sized)) -> RenderPipeline {
         let pipeline = ctx
             .device()
             .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -147,7 +182,10 @@ impl<'a> PipelineBuilder<'a, Render> {
                 },
                 fragment: self.fragment,
                 primitive: wgpu::PrimitiveState::default(),
-                multisample: wgpu::MultisampleState::default(),
+                multisample: wgpu::MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
                 depth_stencil: self.depth_stencil,
                 multiview: None,
                 cache: None,
@@ -171,9 +209,6 @@ macro_rules! impl_into_pass {
                         label: Some(label.into()),
                         pipeline: self,
                         bind_groups: Vec::new(),
-                        vertex_buffers: Vec::new(),
-                        index_buffer: None,
-                        stencil_reference: 0,
                     }
                 }
             }
@@ -186,9 +221,6 @@ pub struct PipelinePass<'a, T> {
     label: Option<String>,
     pipeline: &'a T,
     bind_groups: Vec<&'a wgpu::BindGroup>,
-    vertex_buffers: Vec<&'a wgpu::Buffer>,
-    index_buffer: Option<&'a wgpu::Buffer>,
-    stencil_reference: u32,
 }
 
 impl<'a, T> PipelinePass<'a, T> {
@@ -198,53 +230,6 @@ impl<'a, T> PipelinePass<'a, T> {
     }
 }
 
-impl<'a> PipelinePass<'a, RenderPipeline> {
-    pub fn add_vertex_buffer<'b: 'a>(mut self, vertex_buffer: &'a wgpu::Buffer) -> Self {
-        self.vertex_buffers.push(vertex_buffer);
-        self
-    }
-
-    pub fn set_stencil_reference(mut self, stencil_reference: u32) -> Self {
-        self.stencil_reference = stencil_reference;
-        self
-    }
-
-    pub fn add_index_buffer<'b: 'a>(mut self, index_buffer: &'a wgpu::Buffer) -> Self {
-        self.index_buffer = Some(index_buffer);
-        self
-    }
-
-    pub fn pass(
-        self,
-        encoder: &mut wgpu::CommandEncoder,
-        color_attachments: &[Option<wgpu::RenderPassColorAttachment<'_>>],
-        depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment<'_>>,
-    ) {
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: self.label.as_deref(),
-            color_attachments,
-            depth_stencil_attachment,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
-        render_pass.set_pipeline(self.pipeline);
-        for (i, bind_group) in self.bind_groups.iter().enumerate() {
-            render_pass.set_bind_group(i as u32, bind_group, &[]);
-        }
-        for (i, vertex_buffer) in self.vertex_buffers.iter().enumerate() {
-            render_pass.set_vertex_buffer(i as u32, vertex_buffer.slice(..));
-        }
-        render_pass.set_index_buffer(
-            self.index_buffer.unwrap().slice(..),
-            wgpu::IndexFormat::Uint32,
-        );
-        let indices =
-            self.index_buffer.unwrap().size() / std::mem::size_of::<u32>() as wgpu::BufferAddress;
-        render_pass.set_stencil_reference(self.stencil_reference);
-        render_pass.draw_indexed(0..indices as u32, 0, 0..1);
-    }
-}
-
 impl PipelinePass<'_, ComputePipeline> {
     pub fn pass(self, encoder: &mut wgpu::CommandEncoder, dispatch: (u32, u32, u32)) {
         let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
@@ -0,0 +1,172 @@
+use std::{
+    fs::File,
+    future::Future,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    pin::pin,
+    process::{Child, Command, Stdio},
+    task::Context as TaskContext,
+    time::Duration,
+};
+
+use futures::task::noop_waker_ref;
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, ImageError, ImageResult, RgbaImage,
+};
+
+use crate::texture::Texture;
+
+use super::Scene;
+
+/// Where the frames of a [`VideoRenderer`] end up.
+pub enum VideoOutput {
+    /// Numbered `frame_00000.png` files inside the given directory.
+    PngSequence(PathBuf),
+    Gif(PathBuf),
+    /// Encodes to the given path by piping raw frames into `ffmpeg`.
+    Ffmpeg(PathBuf),
+    /// Raw RGBA frames are written to the stdin of this command.
+    Pipe(Command),
+}
+
+impl VideoOutput {
+    /// Picks the output from the extension: `.gif`, a directory for no extension, `ffmpeg` otherwise.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gif") => Self::Gif(path),
+            Some(_) => Self::Ffmpeg(path),
+            None => Self::PngSequence(path),
+        }
+    }
+}
+
+enum FrameSink {
+    Png {
+        dir: PathBuf,
+        index: usize,
+    },
+    Gif {
+        encoder: GifEncoder<BufWriter<File>>,
+        delay: Delay,
+    },
+    Pipe(Child),
+}
+
+impl FrameSink {
+    fn new(output: VideoOutput, fps: u32, (width, height): (u32, u32)) -> ImageResult<Self> {
+        Ok(match output {
+            VideoOutput::PngSequence(dir) => {
+                std::fs::create_dir_all(&dir)?;
+                Self::Png { dir, index: 0 }
+            }
+            VideoOutput::Gif(path) => {
+                let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+                encoder.set_repeat(Repeat::Infinite)?;
+                Self::Gif {
+                    encoder,
+                    delay: Delay::from_numer_denom_ms(1000, fps),
+                }
+            }
+            VideoOutput::Ffmpeg(path) => {
+                let mut command = Command::new("ffmpeg");
+                command
+                    .args(["-y", "-loglevel", "error", "-f", "rawvideo"])
+                    .args(["-pix_fmt", "rgba", "-s", &format!("{width}x{height}")])
+                    .args(["-r", &fps.to_string(), "-i", "-"])
+                    .args(["-pix_fmt", "yuv420p"])
+                    .arg(path);
+                Self::Pipe(command.stdin(Stdio::piped()).spawn()?)
+            }
+            VideoOutput::Pipe(mut command) => Self::Pipe(command.stdin(Stdio::piped()).spawn()?),
+        })
+    }
+
+    fn write(&mut self, frame: RgbaImage) -> ImageResult<()> {
+        match self {
+            Self::Png { dir, index } => {
+                frame.save(dir.join(format!("frame_{index:05}.png")))?;
+                *index += 1;
+            }
+            Self::Gif { encoder, delay } => {
+                encoder.encode_frame(image::Frame::from_parts(frame, 0, 0, *delay))?;
+            }
+            Self::Pipe(child) => child.stdin.as_mut().unwrap().write_all(&frame)?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> ImageResult<()> {
+        if let Self::Pipe(mut child) = self {
+            drop(child.stdin.take());
+            let status = child.wait()?;
+            if !status.success() {
+                log::error!("Encoder exited with {status}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a scene script offscreen with a fixed timestep instead of wall-clock time.
+pub struct VideoRenderer<'a> {
+    scene: Scene<'a>,
+    output: VideoOutput,
+    fps: u32,
+}
+
+impl<'a> VideoRenderer<'a> {
+    pub fn new(scene: &Scene<'a>, output: VideoOutput) -> Self {
+        Self {
+            scene: scene.clone(),
+            output,
+            fps: 60,
+        }
+    }
+
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.fps = fps.max(1);
+        self
+    }
+
+    /// Drives `construct` to completion, writing one frame per timestep starting at t=0.
+    ///
+    /// Fails if the script awaits something other than the scene, since nothing would advance it.
+    pub fn render(self, construct: impl Future<Output = ()>) -> ImageResult<()> {
+        let (size, target) = {
+            let scene = self.scene.borrow();
+            let size = scene.ctx.size();
+            let target = Texture::create_render_target(
+                scene.ctx.device(),
+                size,
+                scene.ctx.format(),
+                "Video Frame Texture",
+            );
+            (size, target)
+        };
+        let mut sink = FrameSink::new(self.output, self.fps, size)?;
+        let dt = Duration::from_secs_f64(1.0 / self.fps as f64);
+
+        let mut construct = pin!(construct);
+        let mut cx = TaskContext::from_waker(noop_waker_ref());
+        loop {
+            let pending = construct.as_mut().poll(&mut cx).is_pending();
+            let mut scene = self.scene.borrow_mut();
+            // Written before updating, so the first frame is the scene at t=0
+            scene.render_to(&target.view);
+            sink.write(target.read_to_image(scene.ctx.device(), scene.ctx.queue()))?;
+            if !pending {
+                break;
+            }
+            if !scene.is_animating() {
+                // Nothing would ever wake the script up again
+                return Err(ImageError::IoError(io::Error::other(
+                    "scene script is waiting without anything to play",
+                )));
+            }
+            scene.update(dt);
+        }
+        sink.finish()
+    }
+}
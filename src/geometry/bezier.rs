@@ -0,0 +1,51 @@
+//! Helpers for building the quadratic point lists consumed by [`HasPoints`](crate::core::HasPoints).
+
+use cgmath::{InnerSpace, Vector3};
+
+/// A straight segment in quadratic form, ending at `to`.
+pub fn line_to(points: &mut Vec<Vector3<f32>>, to: Vector3<f32>) {
+    let from = *points.last().expect("line_to needs a start point");
+    points.extend([(from + to) * 0.5, to]);
+}
+
+/// Approximates the cubic Bezier from the last point of `points` through `c1`, `c2` to `to`
+/// with quadratic segments, none deviating more than `tolerance` from it.
+pub fn cubic_to(
+    points: &mut Vec<Vector3<f32>>,
+    c1: Vector3<f32>,
+    c2: Vector3<f32>,
+    to: Vector3<f32>,
+    tolerance: f32,
+) {
+    let p0 = *points.last().expect("cubic_to needs a start point");
+    // Error of the single quadratic approximation, which shrinks with the cube of the pieces
+    let error = (to - c2 * 3. + c1 * 3. - p0).magnitude() * 3f32.sqrt() / 36.;
+    let pieces = (error / tolerance).cbrt().ceil().clamp(1., 64.) as usize;
+
+    let at = |t: f32| {
+        let s = 1. - t;
+        p0 * (s * s * s) + c1 * (3. * s * s * t) + c2 * (3. * s * t * t) + to * (t * t * t)
+    };
+    let derivative = |t: f32| {
+        let s = 1. - t;
+        (c1 - p0) * (3. * s * s) + (c2 - c1) * (6. * s * t) + (to - c2) * (3. * t * t)
+    };
+    for i in 0..pieces {
+        let (t0, t1) = (i as f32 / pieces as f32, (i + 1) as f32 / pieces as f32);
+        let (a, b) = (at(t0), at(t1));
+        let dt = (t1 - t0) / 3.;
+        // Midpoint of the two control points of the cubic piece
+        let control =
+            ((a + derivative(t0) * dt) * 3. - a + (b - derivative(t1) * dt) * 3. - b) / 4.;
+        points.extend([control, b]);
+    }
+}
+
+/// Closes a contour with a straight segment back to its start, if it isn't closed already.
+pub fn close(points: &mut Vec<Vector3<f32>>) {
+    if let Some(&first) = points.first() {
+        if points.last() != Some(&first) {
+            line_to(points, first);
+        }
+    }
+}
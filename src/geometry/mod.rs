@@ -1,2 +1,5 @@
-
+pub mod bezier;
 pub mod shapes;
+pub mod svg;
+pub mod tex;
+pub mod text;
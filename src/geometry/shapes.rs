@@ -1,6 +1,8 @@
+use std::rc::Rc;
+
 use cgmath::Vector3;
 
-use crate::core::{HasPoints, Mobject, Shape};
+use crate::core::{HasPoints, Mobject, Path, Shape};
 
 #[derive(Clone)]
 pub struct Square {
@@ -14,7 +16,7 @@ impl Square {
 }
 
 impl HasPoints for Square {
-    fn calc_points(&self) -> Vec<Vector3<f32>> {
+    fn calc_points(&self) -> Path {
         [
             (1., 1., 0.),
             (0., 1., 0.),
@@ -29,6 +31,7 @@ impl HasPoints for Square {
         .into_iter()
         .map(|(x, y, z)| Vector3::new(x, y, z) * self.side * 0.5)
         .collect::<Vec<_>>()
+        .into()
     }
 }
 #[derive(Clone)]
@@ -43,7 +46,7 @@ impl Triangle {
 }
 
 impl HasPoints for Triangle {
-    fn calc_points(&self) -> Vec<Vector3<f32>> {
+    fn calc_points(&self) -> Path {
         [
             (0., 1., 0.),
             (-0.4330127, 0.25, 0.),
@@ -56,6 +59,7 @@ impl HasPoints for Triangle {
         .into_iter()
         .map(|(x, y, z)| Vector3::new(x, y, z) * self.side)
         .collect::<Vec<_>>()
+        .into()
     }
 }
 
@@ -76,7 +80,7 @@ impl Arc {
 }
 
 impl HasPoints for Arc {
-    fn calc_points(&self) -> Vec<Vector3<f32>> {
+    fn calc_points(&self) -> Path {
         let n_components = 8;
         let n_points = 2 * n_components + 1;
         let angles = (0..n_points).map(|i| i as f32 * self.angle / (n_points - 1) as f32);
@@ -89,6 +93,54 @@ impl HasPoints for Arc {
         for i in (1..n_points).step_by(2) {
             points[i as usize] *= handle_adjust;
         }
-        points
+        points.into()
+    }
+}
+
+/// Rectangle displaying an image, see [`Image::new`].
+#[derive(Clone)]
+pub struct Image {
+    width: f32,
+    height: f32,
+}
+
+pub type ImageMobject = Mobject<Image>;
+
+impl Image {
+    /// Displays `image` `height` units tall, keeping its aspect ratio.
+    pub fn new(image: impl Into<Rc<image::DynamicImage>>, height: f32) -> ImageMobject {
+        let image = image.into();
+        let width = height * image.width() as f32 / image.height().max(1) as f32;
+        let mobject = Mobject::new(Shape::new(Self { width, height }));
+        mobject.texture(image);
+        mobject
+    }
+
+    /// Loads a PNG or JPEG, see [`Image::new`].
+    pub fn from_file(
+        path: impl AsRef<std::path::Path>,
+        height: f32,
+    ) -> image::ImageResult<ImageMobject> {
+        Ok(Self::new(image::open(path)?, height))
+    }
+}
+
+impl HasPoints for Image {
+    fn calc_points(&self) -> Path {
+        [
+            (1., 1., 0.),
+            (0., 1., 0.),
+            (-1., 1., 0.),
+            (-1., 0., 0.),
+            (-1., -1., 0.),
+            (0., -1., 0.),
+            (1., -1., 0.),
+            (1., 0., 0.),
+            (1., 1., 0.),
+        ]
+        .into_iter()
+        .map(|(x, y, z)| Vector3::new(x * self.width, y * self.height, z) * 0.5)
+        .collect::<Vec<_>>()
+        .into()
     }
 }
@@ -0,0 +1,921 @@
+//! Minimal SVG reader turning drawn elements into quadratic Bezier contours.
+
+use std::{collections::HashMap, f32::consts::PI, fmt, ops::Index};
+
+use cgmath::{Vector2, Vector3, Vector4};
+
+use super::bezier;
+use crate::core::{FillRule, HasPoints, Mobject, Path, Shape};
+
+const XLINK: &str = "http://www.w3.org/1999/xlink";
+
+#[derive(Debug)]
+pub enum SvgError {
+    Io(std::io::Error),
+    Xml(roxmltree::Error),
+    /// Malformed path data, with the byte offset into the `d` attribute.
+    PathData(usize),
+    Transform(String),
+    MissingReference(String),
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgError::Io(e) => write!(f, "Failed to read SVG: {e}"),
+            SvgError::Xml(e) => write!(f, "Invalid SVG: {e}"),
+            SvgError::PathData(at) => write!(f, "Invalid path data at offset {at}"),
+            SvgError::Transform(t) => write!(f, "Invalid transform `{t}`"),
+            SvgError::MissingReference(id) => write!(f, "Reference to unknown element `{id}`"),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+impl From<std::io::Error> for SvgError {
+    fn from(e: std::io::Error) -> Self {
+        SvgError::Io(e)
+    }
+}
+
+impl From<roxmltree::Error> for SvgError {
+    fn from(e: roxmltree::Error) -> Self {
+        SvgError::Xml(e)
+    }
+}
+
+/// 2D affine transform `(a, b, c, d, e, f)` as in SVG, mapping `(x, y)` to
+/// `(a x + c y + e, b x + d y + f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine([f32; 6]);
+
+impl Affine {
+    pub const IDENTITY: Self = Self([1., 0., 0., 1., 0., 0.]);
+
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self([1., 0., 0., 1., x, y])
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self([x, 0., 0., y, 0., 0.])
+    }
+
+    pub fn rotate(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self([cos, sin, -sin, cos, 0., 0.])
+    }
+
+    /// Applies `other` first, then `self`.
+    pub fn then(&self, other: &Self) -> Self {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = other.0;
+        Self([
+            a * a2 + c * b2,
+            b * a2 + d * b2,
+            a * c2 + c * d2,
+            b * c2 + d * d2,
+            a * e2 + c * f2 + e,
+            b * e2 + d * f2 + f,
+        ])
+    }
+
+    /// How much lengths grow on average, used for stroke widths.
+    pub fn scale_factor(&self) -> f32 {
+        let [a, b, c, d, ..] = self.0;
+        (a * d - b * c).abs().sqrt()
+    }
+
+    pub fn apply(&self, p: Vector2<f32>) -> Vector3<f32> {
+        let [a, b, c, d, e, f] = self.0;
+        Vector3::new(a * p.x + c * p.y + e, b * p.x + d * p.y + f, 0.)
+    }
+
+    /// Parses the value of a `transform` attribute.
+    pub fn parse(value: &str) -> Result<Self, SvgError> {
+        let error = || SvgError::Transform(value.to_string());
+        let mut transform = Self::IDENTITY;
+        let mut rest = value.trim();
+        while !rest.is_empty() {
+            let (name, tail) = rest.split_once('(').ok_or_else(error)?;
+            let (args, tail) = tail.split_once(')').ok_or_else(error)?;
+            let args = numbers(args).ok_or_else(error)?;
+            let next = match (name.trim(), args.as_slice()) {
+                ("matrix", &[a, b, c, d, e, f]) => Self([a, b, c, d, e, f]),
+                ("translate", &[x]) => Self::translate(x, 0.),
+                ("translate", &[x, y]) => Self::translate(x, y),
+                ("scale", &[s]) => Self::scale(s, s),
+                ("scale", &[x, y]) => Self::scale(x, y),
+                ("rotate", &[angle]) => Self::rotate(angle),
+                ("rotate", &[angle, x, y]) => Self::translate(x, y)
+                    .then(&Self::rotate(angle))
+                    .then(&Self::translate(-x, -y)),
+                ("skewX", &[angle]) => Self([1., 0., angle.to_radians().tan(), 1., 0., 0.]),
+                ("skewY", &[angle]) => Self([1., angle.to_radians().tan(), 0., 1., 0., 0.]),
+                _ => return Err(error()),
+            };
+            transform = transform.then(&next);
+            rest = tail.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        }
+        Ok(transform)
+    }
+}
+
+fn numbers(list: &str) -> Option<Vec<f32>> {
+    let mut parser = Parser::new(list);
+    let mut numbers = Vec::new();
+    while parser.at_number() {
+        numbers.push(parser.number().ok()?);
+    }
+    parser.at_end().then_some(numbers)
+}
+
+/// Reads a length attribute, ignoring its unit.
+fn length(value: Option<&str>) -> f32 {
+    value
+        .and_then(|v| Parser::new(v).number().ok())
+        .unwrap_or(0.)
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            data: data.as_bytes(),
+            at: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .data
+            .get(self.at)
+            .is_some_and(|c| c.is_ascii_whitespace() || *c == b',')
+        {
+            self.at += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.at == self.data.len()
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        self.data
+            .get(self.at)
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.'))
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let c = *self.data.get(self.at).filter(|c| c.is_ascii_alphabetic())?;
+        self.at += 1;
+        Some(c)
+    }
+
+    fn number(&mut self) -> Result<f32, SvgError> {
+        self.skip_separators();
+        let start = self.at;
+        let digits = |p: &mut Self| {
+            while p.data.get(p.at).is_some_and(u8::is_ascii_digit) {
+                p.at += 1;
+            }
+        };
+        if matches!(self.data.get(self.at), Some(b'-' | b'+')) {
+            self.at += 1;
+        }
+        digits(self);
+        if self.data.get(self.at) == Some(&b'.') {
+            self.at += 1;
+            digits(self);
+        }
+        // An `e` directly followed by a digit or sign is an exponent, not a unit like `em`
+        if matches!(self.data.get(self.at), Some(b'e' | b'E'))
+            && self
+                .data
+                .get(self.at + 1)
+                .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'-' | b'+'))
+        {
+            self.at += 2;
+            digits(self);
+        }
+        std::str::from_utf8(&self.data[start..self.at])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or(SvgError::PathData(start))
+    }
+
+    /// Arc flags may be written without separators, as in `a1 1 0 01 1 1`.
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        self.skip_separators();
+        let flag = match self.data.get(self.at) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(SvgError::PathData(self.at)),
+        };
+        self.at += 1;
+        Ok(flag)
+    }
+
+    fn point(&mut self) -> Result<Vector2<f32>, SvgError> {
+        Ok(Vector2::new(self.number()?, self.number()?))
+    }
+}
+
+/// Builds contours in output coordinates out of path segments in SVG coordinates.
+struct ContourBuilder {
+    transform: Affine,
+    tolerance: f32,
+    /// Whether every subpath is closed, as needed for filling, or only those ending in `Z`.
+    close: bool,
+    contours: Vec<Vec<Vector3<f32>>>,
+    current: Vec<Vector3<f32>>,
+}
+
+impl ContourBuilder {
+    fn new(transform: Affine, tolerance: f32, close: bool) -> Self {
+        Self {
+            transform,
+            tolerance,
+            close,
+            contours: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+
+    fn move_to(&mut self, p: Vector2<f32>) {
+        self.finish_contour();
+        self.current.push(self.transform.apply(p));
+    }
+
+    fn line_to(&mut self, p: Vector2<f32>) {
+        bezier::line_to(&mut self.current, self.transform.apply(p));
+    }
+
+    fn quad_to(&mut self, c: Vector2<f32>, p: Vector2<f32>) {
+        let (c, p) = (self.transform.apply(c), self.transform.apply(p));
+        self.current.extend([c, p]);
+    }
+
+    fn cubic_to(&mut self, c1: Vector2<f32>, c2: Vector2<f32>, p: Vector2<f32>) {
+        let t = &self.transform;
+        let (c1, c2, p) = (t.apply(c1), t.apply(c2), t.apply(p));
+        bezier::cubic_to(&mut self.current, c1, c2, p, self.tolerance);
+    }
+
+    /// Elliptical arc as in the SVG implementation notes, split into quadratic pieces.
+    #[allow(clippy::too_many_arguments)]
+    fn arc_to(
+        &mut self,
+        from: Vector2<f32>,
+        radii: Vector2<f32>,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vector2<f32>,
+    ) {
+        let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+        if from == to {
+            return;
+        }
+        if rx == 0. || ry == 0. {
+            return self.line_to(to);
+        }
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let half = (from - to) / 2.;
+        let p = Vector2::new(cos * half.x + sin * half.y, -sin * half.x + cos * half.y);
+        let lambda = (p.x * p.x) / (rx * rx) + (p.y * p.y) / (ry * ry);
+        if lambda > 1. {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let num = rx * rx * ry * ry - rx * rx * p.y * p.y - ry * ry * p.x * p.x;
+        let den = rx * rx * p.y * p.y + ry * ry * p.x * p.x;
+        let sign = if large_arc == sweep { -1. } else { 1. };
+        let coef = sign * (num / den).max(0.).sqrt();
+        let center_prime = Vector2::new(coef * rx * p.y / ry, -coef * ry * p.x / rx);
+        let mid = (from + to) / 2.;
+        let center = Vector2::new(
+            cos * center_prime.x - sin * center_prime.y + mid.x,
+            sin * center_prime.x + cos * center_prime.y + mid.y,
+        );
+
+        let angle = |v: Vector2<f32>| v.y.atan2(v.x);
+        let start = angle(Vector2::new(
+            (p.x - center_prime.x) / rx,
+            (p.y - center_prime.y) / ry,
+        ));
+        let end = angle(Vector2::new(
+            (-p.x - center_prime.x) / rx,
+            (-p.y - center_prime.y) / ry,
+        ));
+        let mut delta = end - start;
+        if sweep && delta < 0. {
+            delta += 2. * PI;
+        } else if !sweep && delta > 0. {
+            delta -= 2. * PI;
+        }
+
+        // The midpoint of a quadratic approximating a unit arc of angle `a` bulges out by
+        // (cos(a / 2) + 1 / cos(a / 2)) / 2 - 1
+        let radius = rx.max(ry);
+        let mut pieces = (delta.abs() / (PI / 4.)).ceil().max(1.);
+        while pieces < 64. {
+            let c = (delta / pieces / 2.).cos();
+            if radius * ((c + 1. / c) / 2. - 1.) <= self.tolerance {
+                break;
+            }
+            pieces += 1.;
+        }
+        let on_ellipse = |theta: f32, scale: f32| {
+            let (x, y) = (rx * theta.cos() * scale, ry * theta.sin() * scale);
+            Vector2::new(cos * x - sin * y, sin * x + cos * y) + center
+        };
+        let step = delta / pieces;
+        for i in 0..pieces as usize {
+            let theta = start + step * i as f32;
+            let control = on_ellipse(theta + step / 2., 1. / (step / 2.).cos());
+            let end = if i + 1 == pieces as usize {
+                to
+            } else {
+                on_ellipse(theta + step, 1.)
+            };
+            self.quad_to(control, end);
+        }
+    }
+
+    fn finish_contour(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        if contour.len() >= 3 {
+            if self.close {
+                bezier::close(&mut contour);
+            }
+            self.contours.push(contour);
+        }
+    }
+
+    fn polyline(&mut self, points: &[Vector2<f32>]) {
+        if let Some((&first, rest)) = points.split_first() {
+            self.move_to(first);
+            rest.iter().for_each(|&p| self.line_to(p));
+        }
+    }
+
+    fn ellipse(&mut self, center: Vector2<f32>, radii: Vector2<f32>) {
+        let (right, left) = (
+            center + Vector2::new(radii.x, 0.),
+            center - Vector2::new(radii.x, 0.),
+        );
+        self.move_to(right);
+        self.arc_to(right, radii, 0., false, true, left);
+        self.arc_to(left, radii, 0., false, true, right);
+    }
+
+    fn finish(mut self) -> Vec<Vec<Vector3<f32>>> {
+        self.finish_contour();
+        self.contours
+    }
+}
+
+/// Parses the `d` attribute of a `path` element into contours. With `close`, as for filling,
+/// every subpath is closed rather than only those ending in `Z`.
+pub fn parse_path_data(
+    data: &str,
+    transform: Affine,
+    tolerance: f32,
+    close: bool,
+) -> Result<Vec<Vec<Vector3<f32>>>, SvgError> {
+    let mut parser = Parser::new(data);
+    let mut builder = ContourBuilder::new(transform, tolerance, close);
+    let zero = Vector2::new(0., 0.);
+    let (mut current, mut start) = (zero, zero);
+    // Reflected by the smooth `S` and `T` commands
+    let (mut last_cubic, mut last_quad): (Option<Vector2<f32>>, Option<Vector2<f32>>) =
+        (None, None);
+    let mut command = None;
+
+    while !parser.at_end() {
+        if let Some(c) = parser.command() {
+            command = Some(c);
+        } else if command.is_none() {
+            return Err(SvgError::PathData(parser.at));
+        }
+        let c = command.unwrap();
+        let origin = if c.is_ascii_lowercase() {
+            current
+        } else {
+            zero
+        };
+        let (mut cubic, mut quad) = (None, None);
+        match c.to_ascii_uppercase() {
+            b'M' => {
+                current = origin + parser.point()?;
+                start = current;
+                builder.move_to(current);
+                // Further coordinate pairs are implicit line-tos
+                command = Some(if c == b'm' { b'l' } else { b'L' });
+            }
+            b'L' => {
+                current = origin + parser.point()?;
+                builder.line_to(current);
+            }
+            b'H' => {
+                current.x = origin.x + parser.number()?;
+                builder.line_to(current);
+            }
+            b'V' => {
+                current.y = origin.y + parser.number()?;
+                builder.line_to(current);
+            }
+            b'C' | b'S' => {
+                let c1 = if c.eq_ignore_ascii_case(&b'C') {
+                    origin + parser.point()?
+                } else {
+                    last_cubic.map_or(current, |l| current * 2. - l)
+                };
+                let c2 = origin + parser.point()?;
+                current = origin + parser.point()?;
+                builder.cubic_to(c1, c2, current);
+                cubic = Some(c2);
+            }
+            b'Q' | b'T' => {
+                let control = if c.eq_ignore_ascii_case(&b'Q') {
+                    origin + parser.point()?
+                } else {
+                    last_quad.map_or(current, |l| current * 2. - l)
+                };
+                current = origin + parser.point()?;
+                builder.quad_to(control, current);
+                quad = Some(control);
+            }
+            b'A' => {
+                let radii = parser.point()?;
+                let rotation = parser.number()?;
+                let (large_arc, sweep) = (parser.flag()?, parser.flag()?);
+                let to = origin + parser.point()?;
+                builder.arc_to(current, radii, rotation, large_arc, sweep, to);
+                current = to;
+            }
+            b'Z' => {
+                if current != start {
+                    builder.line_to(start);
+                }
+                current = start;
+                builder.finish_contour();
+                builder.current.push(builder.transform.apply(start));
+                command = None;
+            }
+            _ => return Err(SvgError::PathData(parser.at - 1)),
+        }
+        (last_cubic, last_quad) = (cubic, quad);
+    }
+    Ok(builder.finish())
+}
+
+/// Parses a color in one of the forms `#rgb`, `#rrggbb`, `rgb(r, g, b)` or a basic CSS name,
+/// converted from sRGB to the linear colors shapes are drawn with.
+fn parse_color(value: &str) -> Option<Vector3<f32>> {
+    let decode = |c: f32| match c {
+        c if c <= 0.04045 => c / 12.92,
+        c => ((c + 0.055) / 1.055).powf(2.4),
+    };
+    parse_srgb(value).map(|color| color.map(decode))
+}
+
+fn parse_srgb(value: &str) -> Option<Vector3<f32>> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as f32))
+            .collect::<Option<Vec<_>>>()?;
+        return match *digits.as_slice() {
+            [r, g, b] => Some(Vector3::new(r, g, b) / 15.),
+            [r1, r2, g1, g2, b1, b2] => {
+                Some(Vector3::new(r1 * 16. + r2, g1 * 16. + g2, b1 * 16. + b2) / 255.)
+            }
+            _ => None,
+        };
+    }
+    if let Some(args) = value.strip_prefix("rgb(").and_then(|v| v.strip_suffix(')')) {
+        let channels = args
+            .split(',')
+            .map(|c| match c.trim().strip_suffix('%') {
+                Some(percent) => percent.trim().parse::<f32>().ok().map(|p| p / 100.),
+                None => c.trim().parse::<f32>().ok().map(|c| c / 255.),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        return match *channels.as_slice() {
+            [r, g, b] => Some(Vector3::new(r, g, b).map(|c| c.clamp(0., 1.))),
+            _ => None,
+        };
+    }
+    let hex = match value.to_ascii_lowercase().as_str() {
+        "black" => "#000000",
+        "white" => "#ffffff",
+        "red" => "#ff0000",
+        "lime" => "#00ff00",
+        "blue" => "#0000ff",
+        "yellow" => "#ffff00",
+        "cyan" | "aqua" => "#00ffff",
+        "magenta" | "fuchsia" => "#ff00ff",
+        "gray" | "grey" => "#808080",
+        "silver" => "#c0c0c0",
+        "maroon" => "#800000",
+        "green" => "#008000",
+        "olive" => "#808000",
+        "teal" => "#008080",
+        "navy" => "#000080",
+        "purple" => "#800080",
+        "orange" => "#ffa500",
+        _ => return None,
+    };
+    parse_srgb(hex)
+}
+
+/// Presentation properties, inherited down the document tree.
+#[derive(Debug, Clone, Copy)]
+struct Presentation {
+    fill: Option<Vector3<f32>>,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: Option<Vector3<f32>>,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    /// Product of the `opacity` of the element and its ancestors.
+    opacity: f32,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Self {
+            fill: Some(Vector3::new(0., 0., 0.)),
+            fill_opacity: 1.,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+            stroke_opacity: 1.,
+            stroke_width: 1.,
+            opacity: 1.,
+        }
+    }
+}
+
+impl Presentation {
+    /// Applies the presentation attributes of `node`, then the declarations in its `style`.
+    fn inherit(&self, node: roxmltree::Node) -> Self {
+        let mut style = *self;
+        let attributes = node.attributes().map(|a| (a.name(), a.value()));
+        let declarations = node
+            .attribute("style")
+            .into_iter()
+            .flat_map(|s| s.split(';'))
+            .filter_map(|d| d.split_once(':'))
+            .map(|(name, value)| (name.trim(), value.trim()));
+        for (name, value) in attributes.chain(declarations) {
+            let paint = || match value {
+                "none" => Some(None),
+                value => parse_color(value).map(Some),
+            };
+            let opacity = || value.parse::<f32>().ok().map(|o| o.clamp(0., 1.));
+            match name {
+                "fill" => style.fill = paint().unwrap_or(style.fill),
+                "stroke" => style.stroke = paint().unwrap_or(style.stroke),
+                "fill-opacity" => style.fill_opacity = opacity().unwrap_or(style.fill_opacity),
+                "fill-rule" => match value {
+                    "nonzero" => style.fill_rule = FillRule::NonZero,
+                    "evenodd" => style.fill_rule = FillRule::EvenOdd,
+                    _ => {}
+                },
+                "stroke-opacity" => {
+                    style.stroke_opacity = opacity().unwrap_or(style.stroke_opacity)
+                }
+                "opacity" => style.opacity = self.opacity * opacity().unwrap_or(1.),
+                "stroke-width" if value != "inherit" => style.stroke_width = length(Some(value)),
+                _ => {}
+            }
+        }
+        style
+    }
+
+    fn resolve(&self, transform: &Affine) -> Style {
+        let with_alpha = |rgb: Vector3<f32>, alpha: f32| rgb.extend(alpha * self.opacity);
+        Style {
+            fill: self.fill.map(|rgb| with_alpha(rgb, self.fill_opacity)),
+            fill_rule: self.fill_rule,
+            stroke: self
+                .stroke
+                .filter(|_| self.stroke_width > 0.)
+                .map(|rgb| with_alpha(rgb, self.stroke_opacity)),
+            stroke_width: self.stroke_width * transform.scale_factor(),
+        }
+    }
+}
+
+/// Paint of an element, with the opacities folded into the alpha of its colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// `None` for `fill="none"`.
+    pub fill: Option<Vector4<f32>>,
+    pub fill_rule: FillRule,
+    pub stroke: Option<Vector4<f32>>,
+    /// In the same units as the contours.
+    pub stroke_width: f32,
+}
+
+/// Drawn SVG element converted to contours in SVG user units, with y pointing up.
+#[derive(Debug, Clone)]
+pub struct SvgElement {
+    pub contours: Vec<Vec<Vector3<f32>>>,
+    pub style: Style,
+}
+
+/// Quadratic Bezier outline of an SVG element, with a subpath per contour.
+#[derive(Debug, Clone)]
+pub struct SvgPath {
+    path: Path,
+}
+
+impl SvgPath {
+    pub fn new(contours: impl IntoIterator<Item = Vec<Vector3<f32>>>) -> Self {
+        Self {
+            path: Path::from_subpaths(contours),
+        }
+    }
+}
+
+impl HasPoints for SvgPath {
+    fn calc_points(&self) -> Path {
+        self.path.clone()
+    }
+}
+
+/// Reads every drawn element of an SVG document, in painting order.
+///
+/// Supports `path`, `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon` elements,
+/// groups and `use` references, with their transforms, fills and strokes. Curves are
+/// approximated within `tolerance` user units.
+pub fn parse(svg: &str, tolerance: f32) -> Result<Vec<SvgElement>, SvgError> {
+    read(&roxmltree::Document::parse(svg)?, tolerance)
+}
+
+fn read(document: &roxmltree::Document, tolerance: f32) -> Result<Vec<SvgElement>, SvgError> {
+    let ids = document
+        .descendants()
+        .filter_map(|node| Some((node.attribute("id")?, node)))
+        .collect::<HashMap<_, _>>();
+    let mut reader = Reader {
+        ids,
+        tolerance,
+        elements: Vec::new(),
+    };
+    reader.walk(
+        document.root_element(),
+        Affine::scale(1., -1.),
+        Presentation::default(),
+        false,
+    )?;
+    Ok(reader.elements)
+}
+
+struct Reader<'a, 'input> {
+    ids: HashMap<&'a str, roxmltree::Node<'a, 'input>>,
+    tolerance: f32,
+    elements: Vec<SvgElement>,
+}
+
+impl<'a, 'input> Reader<'a, 'input> {
+    fn walk(
+        &mut self,
+        node: roxmltree::Node<'a, 'input>,
+        parent: Affine,
+        style: Presentation,
+        referenced: bool,
+    ) -> Result<(), SvgError> {
+        if node.attribute("display") == Some("none") {
+            return Ok(());
+        }
+        let transform = match node.attribute("transform") {
+            Some(t) => parent.then(&Affine::parse(t)?),
+            None => parent,
+        };
+        let style = style.inherit(node);
+        let filled = style.fill.is_some();
+        let builder = |close| ContourBuilder::new(transform, self.tolerance, close);
+        let attribute = |name| length(node.attribute(name));
+        let contours = match node.tag_name().name() {
+            "svg" | "g" | "a" => return self.walk_children(node, transform, style),
+            // Only drawn through `use`
+            "symbol" if referenced => return self.walk_children(node, transform, style),
+            "use" => {
+                let href = node
+                    .attribute((XLINK, "href"))
+                    .or_else(|| node.attribute("href"))
+                    .unwrap_or_default();
+                let id = href.trim_start_matches('#');
+                let target = *self
+                    .ids
+                    .get(id)
+                    .ok_or_else(|| SvgError::MissingReference(id.to_string()))?;
+                let offset = Affine::translate(attribute("x"), attribute("y"));
+                return self.walk(target, transform.then(&offset), style, true);
+            }
+            "path" => parse_path_data(
+                node.attribute("d").unwrap_or_default(),
+                transform,
+                self.tolerance,
+                filled,
+            )?,
+            "rect" => {
+                let [x, y, w, h] = ["x", "y", "width", "height"].map(attribute);
+                if w <= 0. || h <= 0. {
+                    return Ok(());
+                }
+                // A missing radius takes the value of the other one
+                let (rx, ry) = (node.attribute("rx"), node.attribute("ry"));
+                let (rx, ry) = (length(rx.or(ry)), length(ry.or(rx)));
+                let (rx, ry) = (rx.clamp(0., w / 2.), ry.clamp(0., h / 2.));
+                let mut builder = builder(true);
+                if rx > 0. && ry > 0. {
+                    let radii = Vector2::new(rx, ry);
+                    builder.move_to(Vector2::new(x + rx, y));
+                    for (start, end) in [
+                        ((x + w - rx, y), (x + w, y + ry)),
+                        ((x + w, y + h - ry), (x + w - rx, y + h)),
+                        ((x + rx, y + h), (x, y + h - ry)),
+                        ((x, y + ry), (x + rx, y)),
+                    ] {
+                        builder.line_to(start.into());
+                        builder.arc_to(start.into(), radii, 0., false, true, end.into());
+                    }
+                } else {
+                    builder.polyline(&[
+                        Vector2::new(x, y),
+                        Vector2::new(x + w, y),
+                        Vector2::new(x + w, y + h),
+                        Vector2::new(x, y + h),
+                    ]);
+                }
+                builder.finish()
+            }
+            "circle" | "ellipse" => {
+                let center = Vector2::new(attribute("cx"), attribute("cy"));
+                let radii = match node.tag_name().name() {
+                    "circle" => Vector2::new(attribute("r"), attribute("r")),
+                    _ => Vector2::new(attribute("rx"), attribute("ry")),
+                };
+                if radii.x <= 0. || radii.y <= 0. {
+                    return Ok(());
+                }
+                let mut builder = builder(true);
+                builder.ellipse(center, radii);
+                builder.finish()
+            }
+            "line" => {
+                let mut builder = builder(false);
+                builder.polyline(&[
+                    Vector2::new(attribute("x1"), attribute("y1")),
+                    Vector2::new(attribute("x2"), attribute("y2")),
+                ]);
+                builder.finish()
+            }
+            name @ ("polyline" | "polygon") => {
+                let points = numbers(node.attribute("points").unwrap_or_default())
+                    .unwrap_or_default()
+                    .chunks_exact(2)
+                    .map(|p| Vector2::new(p[0], p[1]))
+                    .collect::<Vec<_>>();
+                let mut builder = builder(name == "polygon" || filled);
+                builder.polyline(&points);
+                builder.finish()
+            }
+            _ => return Ok(()),
+        };
+        let style = style.resolve(&transform);
+        if !contours.is_empty() && (style.fill.is_some() || style.stroke.is_some()) {
+            self.elements.push(SvgElement { contours, style });
+        }
+        Ok(())
+    }
+
+    fn walk_children(
+        &mut self,
+        node: roxmltree::Node<'a, 'input>,
+        transform: Affine,
+        style: Presentation,
+    ) -> Result<(), SvgError> {
+        for child in node.children().filter(|n| n.is_element()) {
+            self.walk(child, transform, style, false)?;
+        }
+        Ok(())
+    }
+}
+
+/// Larger side of the `viewBox` of a document, or else of its `width` and `height`.
+fn view_size(root: roxmltree::Node) -> Option<f32> {
+    if let Some(&[.., w, h]) = root.attribute("viewBox").and_then(numbers).as_deref() {
+        return Some(w.max(h));
+    }
+    let size = length(root.attribute("width")).max(length(root.attribute("height")));
+    (size > 0.).then_some(size)
+}
+
+/// Centers `elements` on the origin and scales them, by a factor computed from the size of
+/// their bounds.
+pub(crate) fn fit(elements: &mut [SvgElement], scale: impl FnOnce(Vector3<f32>) -> f32) {
+    let points = elements.iter().flat_map(|e| &e.contours).flatten();
+    let Some((min, max)) = points.fold(None, |bounds, &p| {
+        let (min, max) = bounds.unwrap_or((p, p));
+        Some((
+            Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+            Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+        ))
+    }) else {
+        return;
+    };
+    let (center, scale) = ((min + max) * 0.5, scale(max - min));
+    for element in elements {
+        element.style.stroke_width *= scale;
+        for p in element.contours.iter_mut().flatten() {
+            *p = (*p - center) * scale;
+        }
+    }
+}
+
+/// SVG document imported as one [`SvgPath`] per drawn element, keeping their fills and
+/// strokes, centered on the origin.
+pub struct SvgMobject {
+    paths: Vec<Mobject<SvgPath>>,
+}
+
+impl SvgMobject {
+    /// Loads the SVG at `path`, scaled to be `height` world units tall.
+    pub fn from_file(path: impl AsRef<std::path::Path>, height: f32) -> Result<Self, SvgError> {
+        Self::from_svg(&std::fs::read_to_string(path)?, height)
+    }
+
+    pub fn from_svg(svg: &str, height: f32) -> Result<Self, SvgError> {
+        let document = roxmltree::Document::parse(svg)?;
+        // A thousandth of the drawing, if its size is known
+        let tolerance = view_size(document.root_element()).map_or(0.01, |size| size * 1e-3);
+        let mut elements = read(&document, tolerance)?;
+        fit(&mut elements, |size| {
+            if size.y > 0. {
+                height / size.y
+            } else {
+                1.
+            }
+        });
+
+        let paths = elements
+            .into_iter()
+            .map(|SvgElement { contours, style }| {
+                let path = Mobject::new(Shape::new(SvgPath::new(contours)));
+                match style.fill {
+                    Some(color) => path.color(color),
+                    None => path.fill(false),
+                };
+                path.fill_rule(style.fill_rule);
+                if let Some(color) = style.stroke {
+                    path.stroke(style.stroke_width, color);
+                }
+                path
+            })
+            .collect();
+        Ok(Self { paths })
+    }
+
+    pub fn paths(&self) -> &[Mobject<SvgPath>] {
+        &self.paths
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+impl Index<usize> for SvgMobject {
+    type Output = Mobject<SvgPath>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.paths[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a SvgMobject {
+    type Item = &'a Mobject<SvgPath>;
+    type IntoIter = std::slice::Iter<'a, Mobject<SvgPath>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.paths.iter()
+    }
+}
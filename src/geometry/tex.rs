@@ -0,0 +1,209 @@
+use std::{fmt, ops::Index, path::PathBuf, process::Command};
+
+use super::svg::{self, SvgError, SvgPath};
+use crate::core::{Mobject, Shape};
+
+#[derive(Debug)]
+pub enum TexError {
+    /// Reading or writing the cache, or running `latex`/`dvisvgm` failed.
+    Io(std::io::Error),
+    /// `latex` or `dvisvgm` exited with an error, with the end of their output.
+    Compile(String),
+    Svg(SvgError),
+}
+
+impl fmt::Display for TexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TexError::Io(e) => write!(f, "Failed to run latex: {e}"),
+            TexError::Compile(log) => write!(f, "Failed to compile formula:\n{log}"),
+            TexError::Svg(e) => write!(f, "Failed to read formula: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TexError {}
+
+impl From<std::io::Error> for TexError {
+    fn from(e: std::io::Error) -> Self {
+        TexError::Io(e)
+    }
+}
+
+impl From<SvgError> for TexError {
+    fn from(e: SvgError) -> Self {
+        TexError::Svg(e)
+    }
+}
+
+/// Compiles formulas to SVG with a local `latex` and `dvisvgm`, caching the results on disk.
+#[derive(Debug, Clone)]
+pub struct TexCompiler {
+    cache_dir: PathBuf,
+    preamble: String,
+}
+
+impl Default for TexCompiler {
+    fn default() -> Self {
+        Self {
+            cache_dir: std::env::temp_dir().join("webg-tex"),
+            preamble: Self::DEFAULT_PREAMBLE.to_string(),
+        }
+    }
+}
+
+impl TexCompiler {
+    pub const DEFAULT_PREAMBLE: &'static str =
+        "\\documentclass[preview]{standalone}\n\\usepackage{amsmath}\n\\usepackage{amssymb}\n";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+
+    pub fn preamble(mut self, preamble: impl Into<String>) -> Self {
+        self.preamble = preamble.into();
+        self
+    }
+
+    fn document(&self, formula: &str) -> String {
+        format!(
+            "{}\\begin{{document}}\n$\\displaystyle {formula}$\n\\end{{document}}\n",
+            self.preamble
+        )
+    }
+
+    /// Where the SVG for `formula` is cached, named after a hash of the whole document.
+    pub fn cache_path(&self, formula: &str) -> PathBuf {
+        // FNV-1a, which unlike `DefaultHasher` is stable across Rust versions
+        let hash = self
+            .document(formula)
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        self.cache_dir.join(format!("{hash:016x}.svg"))
+    }
+
+    /// Returns the SVG of `formula`, compiling it unless it is cached.
+    pub fn compile(&self, formula: &str) -> Result<String, TexError> {
+        let svg_path = self.cache_path(formula);
+        if let Ok(svg) = std::fs::read_to_string(&svg_path) {
+            return Ok(svg);
+        }
+        std::fs::create_dir_all(&self.cache_dir)?;
+        let tex_path = svg_path.with_extension("tex");
+        let dvi_path = svg_path.with_extension("dvi");
+        std::fs::write(&tex_path, self.document(formula))?;
+
+        let result = Self::run(
+            Command::new("latex")
+                .args(["-interaction=nonstopmode", "-halt-on-error"])
+                .arg("-output-directory")
+                .arg(&self.cache_dir)
+                .arg(&tex_path),
+        )
+        .and_then(|_| {
+            Self::run(
+                Command::new("dvisvgm")
+                    .args(["--no-fonts", "--exact-bbox", "--verbosity=1"])
+                    .arg("-o")
+                    .arg(&svg_path)
+                    .arg(&dvi_path),
+            )
+        });
+        for extension in ["tex", "dvi", "aux", "log"] {
+            let _ = std::fs::remove_file(svg_path.with_extension(extension));
+        }
+        result?;
+        Ok(std::fs::read_to_string(&svg_path)?)
+    }
+
+    fn run(command: &mut Command) -> Result<(), TexError> {
+        let output = command.output()?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let log = String::from_utf8_lossy(&output.stdout) + String::from_utf8_lossy(&output.stderr);
+        let tail = log.lines().rev().take(20).collect::<Vec<_>>();
+        Err(TexError::Compile(
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+        ))
+    }
+}
+
+/// A typeset formula with one submobject per glyph (and rule, like fraction bars).
+///
+/// The glyphs are positioned in a shared frame centered on the origin, so they can be added
+/// to a scene and animated individually:
+/// `for glyph in tex.glyphs() { scene.add(glyph); }`.
+pub struct MathTex {
+    glyphs: Vec<Mobject<SvgPath>>,
+}
+
+impl MathTex {
+    /// Height of an em in TeX points, at the default 10pt font size.
+    const EM: f32 = 10.;
+
+    /// Typesets `formula` with the default [`TexCompiler`]. `size` is the height of an em in
+    /// world units.
+    pub fn new(formula: &str, size: f32) -> Result<Self, TexError> {
+        Self::with_compiler(&TexCompiler::default(), formula, size)
+    }
+
+    pub fn with_compiler(
+        compiler: &TexCompiler,
+        formula: &str,
+        size: f32,
+    ) -> Result<Self, TexError> {
+        Ok(Self::from_svg(&compiler.compile(formula)?, size)?)
+    }
+
+    /// Builds the glyphs from an SVG as produced by `dvisvgm --no-fonts`, in points.
+    pub fn from_svg(svg: &str, size: f32) -> Result<Self, SvgError> {
+        let mut elements = svg::parse(svg, 0.01)?;
+        svg::fit(&mut elements, |_| size / Self::EM);
+        let glyphs = elements
+            .into_iter()
+            .map(|element| {
+                let glyph = Mobject::new(Shape::new(SvgPath::new(element.contours)));
+                glyph.fill_rule(element.style.fill_rule);
+                glyph
+            })
+            .collect();
+        Ok(Self { glyphs })
+    }
+
+    pub fn glyphs(&self) -> &[Mobject<SvgPath>] {
+        &self.glyphs
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+}
+
+impl Index<usize> for MathTex {
+    type Output = Mobject<SvgPath>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.glyphs[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a MathTex {
+    type Item = &'a Mobject<SvgPath>;
+    type IntoIter = std::slice::Iter<'a, Mobject<SvgPath>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.glyphs.iter()
+    }
+}
@@ -0,0 +1,251 @@
+use std::{fmt, rc::Rc};
+
+use cgmath::Vector3;
+use ttf_parser::{
+    gpos::{PairAdjustment, PositioningSubtable},
+    opentype_layout::LayoutTable,
+    Face, GlyphId, OutlineBuilder, Tag,
+};
+
+use super::bezier;
+use crate::core::{FillRule, HasPoints, Mobject, Path, Shape};
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    Parse(ttf_parser::FaceParsingError),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(e) => write!(f, "Failed to read font: {e}"),
+            FontError::Parse(e) => write!(f, "Failed to parse font: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(e: std::io::Error) -> Self {
+        FontError::Io(e)
+    }
+}
+
+impl From<ttf_parser::FaceParsingError> for FontError {
+    fn from(e: ttf_parser::FaceParsingError) -> Self {
+        FontError::Parse(e)
+    }
+}
+
+/// A TrueType or OpenType font, cheap to clone.
+#[derive(Clone)]
+pub struct Font {
+    data: Rc<[u8]>,
+    index: u32,
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font").field("index", &self.index).finish()
+    }
+}
+
+impl Font {
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, FontError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Loads the first face of a font file or collection.
+    pub fn from_bytes(data: impl Into<Rc<[u8]>>) -> Result<Self, FontError> {
+        let data = data.into();
+        Face::parse(&data, 0)?;
+        Ok(Self { data, index: 0 })
+    }
+
+    fn face(&self) -> Face<'_> {
+        Face::parse(&self.data, self.index).expect("font was validated on load")
+    }
+}
+
+/// Collects glyph outlines as closed quadratic contours, scaled and moved into place.
+struct GlyphOutline {
+    contours: Vec<Vec<Vector3<f32>>>,
+    offset: Vector3<f32>,
+    scale: f32,
+}
+
+impl GlyphOutline {
+    fn point(&self, x: f32, y: f32) -> Vector3<f32> {
+        self.offset + Vector3::new(x, y, 0.) * self.scale
+    }
+
+    fn current(&mut self) -> &mut Vec<Vector3<f32>> {
+        self.contours
+            .last_mut()
+            .expect("outline starts with move_to")
+    }
+}
+
+impl OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        self.contours.push(vec![p]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.point(x, y);
+        bezier::line_to(self.current(), p);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (c, p) = (self.point(x1, y1), self.point(x, y));
+        self.current().extend([c, p]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (c1, c2, p) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        // Within one font unit of the outline
+        let tolerance = self.scale;
+        bezier::cubic_to(self.current(), c1, c2, p, tolerance);
+    }
+
+    fn close(&mut self) {
+        bezier::close(self.current());
+    }
+}
+
+/// Text laid out from glyph outlines, centered on the origin.
+///
+/// `size` is the height of an em in world units. Lines are split on `\n` and kerned with
+/// the pair adjustments of the font's `GPOS` table, or its legacy `kern` table if `GPOS` has
+/// no kerning.
+/// Other OpenType layout features, like ligatures or contextual kerning, are not applied.
+#[derive(Clone)]
+pub struct Text {
+    text: String,
+    font: Font,
+    size: f32,
+}
+
+impl Text {
+    pub fn new(text: impl Into<String>, font: &Font, size: f32) -> Mobject<Text> {
+        let text = Mobject::new(Shape::new(Self {
+            text: text.into(),
+            font: font.clone(),
+            size,
+        }));
+        // Glyph outlines are wound so that overlapping contours add up
+        text.fill_rule(FillRule::NonZero);
+        text
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn kerning(face: &Face, left: GlyphId, right: GlyphId) -> i16 {
+        let tables = face.tables();
+        tables
+            .gpos
+            .and_then(|gpos| Self::gpos_kerning(gpos, left, right))
+            .unwrap_or_else(|| {
+                tables
+                    .kern
+                    .into_iter()
+                    .flat_map(|kern| kern.subtables)
+                    .filter(|table| table.horizontal && !table.variable)
+                    .find_map(|table| table.glyphs_kerning(left, right))
+                    .unwrap_or(0)
+            })
+    }
+
+    /// Sums the advance adjustments of the `kern` feature lookups, or `None` without any.
+    /// Within a lookup the first subtable with an entry for the pair applies, as in a shaper.
+    fn gpos_kerning(gpos: LayoutTable, left: GlyphId, right: GlyphId) -> Option<i16> {
+        // Features are listed once per script, often with the same lookups
+        let mut lookups = gpos
+            .features
+            .into_iter()
+            .filter(|feature| feature.tag == Tag::from_bytes(b"kern"))
+            .flat_map(|feature| feature.lookup_indices)
+            .collect::<Vec<_>>();
+        lookups.sort_unstable();
+        lookups.dedup();
+        if lookups.is_empty() {
+            return None;
+        }
+
+        let kerning = lookups
+            .into_iter()
+            .filter_map(|index| gpos.lookups.get(index))
+            .filter_map(|lookup| {
+                lookup
+                    .subtables
+                    .into_iter::<PositioningSubtable>()
+                    .find_map(|subtable| match subtable {
+                        PositioningSubtable::Pair(PairAdjustment::Format1 { coverage, sets }) => {
+                            let (record, _) = sets.get(coverage.get(left)?)?.get(right)?;
+                            Some(record.x_advance)
+                        }
+                        PositioningSubtable::Pair(PairAdjustment::Format2 {
+                            coverage,
+                            classes,
+                            matrix,
+                        }) => {
+                            coverage.get(left)?;
+                            let classes = (classes.0.get(left), classes.1.get(right));
+                            Some(matrix.get(classes)?.0.x_advance)
+                        }
+                        _ => None,
+                    })
+            })
+            .fold(0, i16::saturating_add);
+        Some(kerning)
+    }
+
+    fn contours(&self) -> Vec<Vec<Vector3<f32>>> {
+        let face = self.font.face();
+        let scale = self.size / face.units_per_em() as f32;
+        let line_height = (face.ascender() - face.descender() + face.line_gap()) as f32 * scale;
+
+        let mut outline = GlyphOutline {
+            contours: Vec::new(),
+            offset: Vector3::new(0., 0., 0.),
+            scale,
+        };
+        for (row, line) in self.text.lines().enumerate() {
+            outline.offset = Vector3::new(0., -(row as f32) * line_height, 0.);
+            let mut previous = None;
+            for c in line.chars() {
+                let glyph = face.glyph_index(c).unwrap_or(GlyphId(0));
+                if let Some(previous) = previous {
+                    outline.offset.x += Self::kerning(&face, previous, glyph) as f32 * scale;
+                }
+                face.outline_glyph(glyph, &mut outline);
+                outline.offset.x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+                previous = Some(glyph);
+            }
+        }
+        outline.contours
+    }
+}
+
+impl HasPoints for Text {
+    fn calc_points(&self) -> Path {
+        let mut points = Path::from_subpaths(self.contours());
+        let Some(&first) = points.first() else {
+            return points;
+        };
+        let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+            (
+                Vector3::new(min.x.min(p.x), min.y.min(p.y), 0.),
+                Vector3::new(max.x.max(p.x), max.y.max(p.y), 0.),
+            )
+        });
+        let center = (min + max) * 0.5;
+        points.iter_mut().for_each(|p| *p -= center);
+        points
+    }
+}
@@ -0,0 +1,4 @@
+pub mod animations;
+pub mod core;
+pub mod geometry;
+pub mod texture;
@@ -1,14 +1,10 @@
-mod animations;
-mod core;
-mod geometry;
-mod texture;
-
-use animations::Transformation;
-use core::{Scene, SurfaceContext};
 use futures::executor::LocalPool;
 use futures::task::LocalSpawnExt;
-use geometry::shapes::{Arc, Square, Triangle};
 use std::{ops::Deref, rc::Rc, time::Instant};
+use webg::animations::Transformation;
+use webg::core::color::BLUE;
+use webg::core::{self, Scene, SurfaceContext, VideoOutput, VideoRenderer};
+use webg::geometry::shapes::{Arc, Square, Triangle};
 use winit::{
     event::{Event, WindowEvent},
     event_loop::EventLoop,
@@ -28,13 +24,32 @@ async fn construct(scene: Scene<'_>) {
     let q = q1.clone();
     scene.add(&q);
     scene.play(Transformation::new(&q, &q2, 1.)).await;
+    scene.wait(0.5).await;
     scene.play(Transformation::new(&q, &q3, 2.)).await;
-
-    // q2.animate().
+    scene
+        .play(q.animate().shift((0.5, 0.0, 0.0)).scale(0.5).color(BLUE))
+        .await;
 }
 
 #[pollster::main]
 async fn main() {
+    env_logger::init();
+    match std::env::args().nth(1) {
+        Some(output) => render_video(output).await,
+        None => run_window().await,
+    }
+}
+
+async fn render_video(output: String) {
+    let ctx = core::Context::init().await.attach_texture(1280, 720);
+    let scene = Scene::with_msaa(ctx, 4);
+    VideoRenderer::new(&scene, VideoOutput::from_path(output))
+        .fps(60)
+        .render(construct(scene.clone()))
+        .expect("Failed to render video");
+}
+
+async fn run_window() {
     let event_loop = EventLoop::new().unwrap();
     let window = Rc::new(
         winit::window::WindowBuilder::new()
@@ -43,7 +58,6 @@ async fn main() {
             .unwrap(),
     );
 
-    env_logger::init();
     let ctx: SurfaceContext<'_>;
     unsafe {
         let window: *const Window = window.deref();
@@ -51,7 +65,7 @@ async fn main() {
             .await
             .attach_window(window.as_ref().unwrap());
     }
-    let scene = Scene::new(ctx);
+    let scene = Scene::with_msaa(ctx, 4);
 
     let mut local_pool = LocalPool::new();
     local_pool
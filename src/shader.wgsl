@@ -4,12 +4,35 @@ struct CameraUniform {
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
+struct Gradient {
+    colors: array<vec4<f32>, 4>,
+    offsets: vec4<f32>,
+    start: vec2<f32>,
+    end: vec2<f32>,
+    radius: f32,
+    // 0: solid, 1: linear, 2: radial
+    kind: u32,
+};
+
 struct ObjectUniforms {
     model: mat4x4<f32>,
     color: vec4<f32>,
+    stroke_color: vec4<f32>,
+    gradient: Gradient,
+    // min.xy, max.xy
+    bounds: vec4<f32>,
+    textured: u32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
 };
 @group(1) @binding(0)
 var<uniform> uniforms: ObjectUniforms;
+// Set for objects whose colors and texture already have their alpha multiplied in
+override premultiplied: bool = false;
+@group(2) @binding(0)
+var fill_texture: texture_2d<f32>;
+@group(2) @binding(1)
+var fill_sampler: sampler;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -18,8 +41,11 @@ struct VertexInput {
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) uv: vec2<f32>,
+    // Evaluated per sample, so the curve test in `stencil` is antialiased under MSAA
+    @location(0) @interpolate(perspective, sample) uv: vec2<f32>,
     @location(1) col: vec4<f32>,
+    // Position in the shape's own coordinates, where gradients are defined
+    @location(2) local: vec2<f32>,
 };
 
 @vertex
@@ -30,6 +56,7 @@ fn vs_main(
     out.uv = model.uv;
     out.clip_position = camera.view_proj * uniforms.model * vec4<f32>(model.position, 1.0);
     out.col = uniforms.color;
+    out.local = model.position.xy;
     // out.col = vec4<f32>(vec3<f32>(rand(model.uv + model.position.xy)), 1.0);
     return out;
 }
@@ -45,7 +72,53 @@ fn stencil(in: VertexOutput) {
     }
 }
 
+// Clears the stencil under a stroke once it is drawn
+@fragment
+fn reset(in: VertexOutput) {}
+
+fn gradient_color(p: vec2<f32>) -> vec4<f32> {
+    let g = uniforms.gradient;
+    var s: f32;
+    if (g.kind == 1u) {
+        let d = g.end - g.start;
+        s = dot(p - g.start, d) / max(dot(d, d), 1e-8);
+    } else {
+        s = length(p - g.start) / max(g.radius, 1e-8);
+    }
+    // Stops are sorted, so blending towards each one in turn gives the piecewise linear ramp
+    var color = uniforms.gradient.colors[0];
+    for (var i = 1; i < 4; i++) {
+        let lo = uniforms.gradient.offsets[i - 1];
+        let hi = uniforms.gradient.offsets[i];
+        let f = select(step(hi, s), clamp((s - lo) / (hi - lo), 0.0, 1.0), hi > lo);
+        color = mix(color, uniforms.gradient.colors[i], f);
+    }
+    return color;
+}
+
+// Blending expects premultiplied colors
+fn premultiply(color: vec4<f32>, opacity: f32) -> vec4<f32> {
+    if (premultiplied) {
+        return color * opacity;
+    }
+    let alpha = color.a * opacity;
+    return vec4<f32>(color.rgb * alpha, alpha);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return in.col;
+    // Image rows go down, so v is measured from the top of the bounding box
+    let size = max(uniforms.bounds.zw - uniforms.bounds.xy, vec2<f32>(1e-8));
+    let uv = vec2<f32>(in.local.x - uniforms.bounds.x, uniforms.bounds.w - in.local.y) / size;
+    let texel = select(vec4<f32>(1.0), textureSample(fill_texture, fill_sampler, uv), uniforms.textured != 0u);
+
+    if (uniforms.gradient.kind == 0u) {
+        return premultiply(texel * in.col, uniforms.fill_opacity);
+    }
+    return premultiply(texel * gradient_color(in.local), uniforms.fill_opacity);
+}
+
+@fragment
+fn fs_stroke(in: VertexOutput) -> @location(0) vec4<f32> {
+    return premultiply(uniforms.stroke_color, uniforms.stroke_opacity);
 }
\ No newline at end of file
@@ -0,0 +1,373 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use futures::{executor::LocalPool, task::LocalSpawnExt};
+use webg::animations::easing::{EaseInQuad, Linear};
+use webg::animations::{
+    Animatable, Animation, AnimationGroup, FadeIn, FadeOut, FadeTransform, LaggedStart, Sequence,
+    Timed, Transformation, WaitUntil,
+};
+use webg::core::{Context, Renderable, Scene};
+use webg::geometry::shapes::{Square, Triangle};
+
+const DT: Duration = Duration::from_millis(100);
+
+fn scene() -> Scene<'static> {
+    let ctx = pollster::block_on(Context::init()).attach_texture(64, 64);
+    Scene::new(ctx)
+}
+
+fn transformation(scene: &Scene<'static>, duration: f32) -> impl Animatable {
+    let square = Square::new(1.);
+    scene.add(&square);
+    Transformation::new(&square, &Triangle::new(1.), duration)
+}
+
+/// Steps the scene until `done` is set, returning the number of frames it took.
+fn run_until(scene: &Scene<'static>, pool: &mut LocalPool, done: &Cell<bool>) -> usize {
+    let mut frames = 0;
+    while {
+        pool.run_until_stalled();
+        !done.get()
+    } {
+        assert!(frames < 1000, "animation never finished");
+        scene.borrow_mut().update(DT);
+        frames += 1;
+    }
+    frames
+}
+
+#[test]
+fn concurrent_plays_resolve_independently() {
+    let scene = scene();
+    let mut pool = LocalPool::new();
+    let short_done = Rc::new(Cell::new(false));
+    let long_done = Rc::new(Cell::new(false));
+
+    for (duration, done) in [(0.5, short_done.clone()), (1.0, long_done.clone())] {
+        let scene = scene.clone();
+        let anim = transformation(&scene, duration);
+        pool.spawner()
+            .spawn_local(async move {
+                scene.play(anim).await;
+                done.set(true);
+            })
+            .unwrap();
+    }
+
+    let short_frames = run_until(&scene, &mut pool, &short_done);
+    assert!(!long_done.get());
+    let long_frames = run_until(&scene, &mut pool, &long_done);
+    assert!(short_frames > 0 && long_frames > 0);
+    assert!(!scene.borrow().is_animating());
+}
+
+#[test]
+fn play_all_waits_for_longest() {
+    let scene = scene();
+    let mut pool = LocalPool::new();
+    let done = Rc::new(Cell::new(false));
+
+    let anims: Vec<Box<dyn Animatable>> = vec![
+        Box::new(transformation(&scene, 0.3)),
+        Box::new(transformation(&scene, 1.0)),
+    ];
+    pool.spawner()
+        .spawn_local({
+            let scene = scene.clone();
+            let done = done.clone();
+            async move {
+                scene.play_all(anims).await;
+                done.set(true);
+            }
+        })
+        .unwrap();
+
+    let frames = run_until(&scene, &mut pool, &done);
+    assert!(frames as f32 * DT.as_secs_f32() >= 1.0);
+}
+
+#[test]
+fn wait_uses_scene_clock() {
+    let scene = scene();
+    let mut pool = LocalPool::new();
+    let done = Rc::new(Cell::new(false));
+    let ticks = Rc::new(Cell::new(0));
+
+    pool.spawner()
+        .spawn_local({
+            let scene = scene.clone();
+            let done = done.clone();
+            let ticks = ticks.clone();
+            async move {
+                scene.wait(1.0).await;
+                scene
+                    .wait_until(move || {
+                        ticks.set(ticks.get() + 1);
+                        ticks.get() == 3
+                    })
+                    .await;
+                done.set(true);
+            }
+        })
+        .unwrap();
+
+    // 10 frames of 0.1s add up to just over 1s, then 3 to satisfy the predicate
+    assert_eq!(run_until(&scene, &mut pool, &done), 13);
+}
+
+/// Begin calls are logged with a time of -1.
+type Log = Rc<RefCell<Vec<(&'static str, f32)>>>;
+
+/// Records the local times it is begun and applied at.
+struct Probe {
+    duration: f32,
+    log: Log,
+    name: &'static str,
+}
+
+impl Animatable for Probe {
+    fn apply(&mut self, time: f32) -> bool {
+        self.log.borrow_mut().push((self.name, time));
+        time <= self.duration
+    }
+
+    fn begin(&mut self) {
+        self.log.borrow_mut().push((self.name, -1.));
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+}
+
+fn probes(durations: &[(&'static str, f32)]) -> (Vec<Box<dyn Animatable>>, Log) {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let anims = durations
+        .iter()
+        .map(|&(name, duration)| {
+            Box::new(Probe {
+                duration,
+                log: log.clone(),
+                name,
+            }) as Box<dyn Animatable>
+        })
+        .collect();
+    (anims, log)
+}
+
+#[test]
+fn group_durations() {
+    let durations = [("a", 1.0), ("b", 2.0), ("c", 0.5)];
+    let parallel = probes(&durations).0.into_iter().collect::<AnimationGroup>();
+    assert_eq!(parallel.duration(), 2.0);
+    let sequence = probes(&durations).0.into_iter().collect::<Sequence>();
+    assert_eq!(sequence.duration(), 3.5);
+    let lagged = probes(&durations)
+        .0
+        .into_iter()
+        .collect::<AnimationGroup>()
+        .lag_ratio(0.5);
+    assert_eq!(lagged.duration(), 2.5);
+    assert_eq!(LaggedStart::new(0.5).duration(), 0.0);
+}
+
+#[test]
+fn sequence_begins_children_up_front_and_finishes_them() {
+    let (anims, log) = probes(&[("a", 1.0), ("b", 1.0)]);
+    let mut sequence = anims.into_iter().collect::<Sequence>();
+    sequence.begin();
+    // Later children first, so the first one's start state is left on shared mobjects
+    assert_eq!(
+        *log.borrow(),
+        [("b", -1.), ("b", 0.), ("a", -1.), ("a", 0.)]
+    );
+
+    log.borrow_mut().clear();
+    assert!(sequence.apply(0.5));
+    assert_eq!(*log.borrow(), [("a", 0.5)]);
+
+    log.borrow_mut().clear();
+    assert!(sequence.apply(1.5));
+    assert_eq!(*log.borrow(), [("a", 1.0), ("b", 0.5)]);
+
+    log.borrow_mut().clear();
+    assert!(!sequence.apply(2.5));
+    assert_eq!(*log.borrow(), [("b", 1.0)]);
+}
+
+#[test]
+fn lagged_fades_are_hidden_until_they_start() {
+    let (a, b) = (Square::new(1.), Square::new(1.));
+    let mut lagged = LaggedStart::new(0.5)
+        .with(FadeIn::new(&a, 1.).with_easing(Linear))
+        .with(FadeIn::new(&b, 1.).with_easing(Linear));
+    lagged.begin();
+    assert_eq!(b.borrow().fill_opacity(), 0.);
+    lagged.apply(0.25);
+    assert_eq!(a.borrow().fill_opacity(), 0.25);
+    assert_eq!(b.borrow().fill_opacity(), 0.);
+    lagged.apply(1.);
+    assert_eq!(b.borrow().fill_opacity(), 0.5);
+}
+
+#[test]
+fn animation_wrapper_remaps_time() {
+    let (mut anims, log) = probes(&[("a", 1.0)]);
+    let mut anim = Animation::new(anims.remove(0))
+        .with_duration(2.0)
+        .with_delay(1.0)
+        .with_easing(EaseInQuad);
+    assert_eq!(anim.duration(), 3.0);
+
+    anim.begin();
+    assert!(anim.apply(0.5));
+    assert!(anim.apply(2.0));
+    // The first apply past the end still applies the final state
+    assert!(!anim.apply(3.5));
+    assert_eq!(
+        *log.borrow(),
+        [("a", -1.), ("a", 0.), ("a", 0.25), ("a", 1.)]
+    );
+}
+
+#[test]
+fn animation_wrapper_delays_unbounded_animations() {
+    let (mut anims, log) = probes(&[("a", f32::INFINITY)]);
+    let mut anim = Animation::new(anims.remove(0))
+        .with_delay(1.0)
+        .with_easing(EaseInQuad);
+    anim.begin();
+    assert!(anim.apply(0.5));
+    assert!(anim.apply(1.5));
+    assert_eq!(*log.borrow(), [("a", -1.), ("a", 0.), ("a", 0.5)]);
+
+    let done = Rc::new(Cell::new(false));
+    let mut wait = Animation::new(WaitUntil::new({
+        let done = done.clone();
+        move || done.get()
+    }))
+    .with_delay(1.0)
+    .with_easing(EaseInQuad);
+    wait.begin();
+    assert!(wait.apply(0.5));
+    assert!(wait.apply(1.5));
+    done.set(true);
+    assert!(!wait.apply(1.6));
+}
+
+#[test]
+fn instant_animations_wait_for_their_delay() {
+    let (mut anims, log) = probes(&[("a", 1.0)]);
+    let mut anim = Animation::new(anims.remove(0))
+        .with_duration(0.)
+        .with_delay(1.0);
+    anim.begin();
+    assert!(anim.apply(0.5));
+    assert!(!anim.apply(1.5));
+    assert_eq!(*log.borrow(), [("a", -1.), ("a", 0.), ("a", 1.)]);
+}
+
+#[test]
+fn animations_end_in_their_final_state_when_frames_miss_the_end() {
+    let scene = scene();
+    let mut pool = LocalPool::new();
+    let done = Rc::new(Cell::new(false));
+    let square = Square::new(1.);
+    scene.add(&square);
+
+    pool.spawner()
+        .spawn_local({
+            let scene = scene.clone();
+            let done = done.clone();
+            let fade_out = FadeOut::new(&square, 1.).with_easing(Linear);
+            async move {
+                scene.play(fade_out).await;
+                done.set(true);
+            }
+        })
+        .unwrap();
+
+    // 0.3s frames land at 0.9 and 1.2, never on the end
+    while {
+        pool.run_until_stalled();
+        !done.get()
+    } {
+        scene.borrow_mut().update(Duration::from_millis(300));
+    }
+    assert_eq!(square.borrow().fill_opacity(), 0.);
+}
+
+#[test]
+fn fades_scale_the_opacity() {
+    let square = Square::new(1.);
+    square.fill_opacity(0.8).stroke_opacity(0.4);
+    let opacity = || {
+        (
+            square.borrow().fill_opacity(),
+            square.borrow().stroke_opacity(),
+        )
+    };
+
+    let mut fade_out = FadeOut::new(&square, 1.).with_easing(Linear);
+    fade_out.begin();
+    fade_out.apply(0.5);
+    assert_eq!(opacity(), (0.4, 0.2));
+    fade_out.apply(1.);
+    assert_eq!(opacity(), (0., 0.));
+
+    square.opacity(1.);
+    let mut fade_in = FadeIn::new(&square, 1.).to(0.5).with_easing(Linear);
+    fade_in.begin();
+    assert_eq!(opacity(), (0., 0.));
+    fade_in.apply(0.5);
+    assert_eq!(opacity(), (0.25, 0.25));
+    assert!(!fade_in.apply(1.5));
+    assert_eq!(opacity(), (0.5, 0.5));
+}
+
+#[test]
+fn lagged_fade_ins_show_nothing_at_the_start() {
+    let scene = scene();
+    let squares = [Square::new(1.), Square::new(0.5)];
+    let mut lagged = LaggedStart::new(0.5);
+    for square in &squares {
+        scene.add(square);
+        lagged = lagged.with(FadeIn::new(square, 1.));
+    }
+    lagged.begin();
+    assert!(scene.capture().pixels().all(|pixel| pixel.0[3] == 0));
+}
+
+#[test]
+fn fade_out_then_in_restores_the_mobject() {
+    let square = Square::new(1.);
+    for mut fade in [
+        Box::new(FadeOut::new(&square, 1.)) as Box<dyn Animatable>,
+        Box::new(FadeIn::new(&square, 1.)),
+    ] {
+        fade.begin();
+        fade.apply(1.);
+    }
+    assert_eq!(square.borrow().fill_opacity(), 1.);
+    assert_eq!(square.borrow().stroke_opacity(), 1.);
+}
+
+#[test]
+fn fade_transform_crosses_over() {
+    let (square, triangle) = (Square::new(1.), Triangle::new(1.));
+    triangle.shift((1., 0., 0.)).opacity(0.6);
+
+    let mut anim = FadeTransform::new(&square, &triangle, 1.);
+    anim.begin();
+    anim.apply(0.);
+    assert_eq!(triangle.borrow().fill_opacity(), 0.);
+    anim.apply(1.);
+    assert_eq!(square.borrow().fill_opacity(), 0.);
+    assert!((triangle.borrow().fill_opacity() - 0.6).abs() < 1e-6);
+    // Both end up where the target was
+    assert_eq!(square.borrow().model(), triangle.borrow().model());
+}
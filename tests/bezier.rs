@@ -0,0 +1,42 @@
+use cgmath::{InnerSpace, Vector3};
+use webg::geometry::bezier;
+
+fn cubic(p: [Vector3<f32>; 4], t: f32) -> Vector3<f32> {
+    let s = 1. - t;
+    p[0] * (s * s * s) + p[1] * (3. * s * s * t) + p[2] * (3. * s * t * t) + p[3] * (t * t * t)
+}
+
+fn quadratic(p: &[Vector3<f32>], t: f32) -> Vector3<f32> {
+    let s = 1. - t;
+    p[0] * (s * s) + p[1] * (2. * s * t) + p[2] * (t * t)
+}
+
+#[test]
+fn cubic_to_stays_within_tolerance() {
+    let p = [
+        Vector3::new(0., 0., 0.),
+        Vector3::new(0.2, 1.5, 0.),
+        Vector3::new(1.4, -0.8, 0.),
+        Vector3::new(1., 1., 0.),
+    ];
+    for tolerance in [0.1, 0.01, 0.001] {
+        let mut points = vec![p[0]];
+        bezier::cubic_to(&mut points, p[1], p[2], p[3], tolerance);
+        assert_eq!(points.len() % 2, 1);
+        assert_eq!(*points.last().unwrap(), p[3]);
+
+        let samples = (0..=20000)
+            .map(|i| cubic(p, i as f32 / 20000.))
+            .collect::<Vec<_>>();
+        for segment in points.windows(3).step_by(2) {
+            for i in 0..=20 {
+                let q = quadratic(segment, i as f32 / 20.);
+                let distance = samples
+                    .iter()
+                    .map(|s| (s - q).magnitude())
+                    .fold(f32::INFINITY, f32::min);
+                assert!(distance <= tolerance * 1.5, "{distance} > {tolerance}");
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use webg::core::color::{BLUE, RED};
+use webg::core::{BlendMode, Context, FillRule, Gradient, Scene};
+use webg::geometry::shapes::{Arc, Square};
+use webg::geometry::svg::SvgMobject;
+
+const SIZE: u32 = 128;
+
+fn scene() -> Scene<'static> {
+    let ctx = pollster::block_on(Context::init()).attach_texture(SIZE, SIZE);
+    Scene::new(ctx)
+}
+
+/// Coordinates of the path data, which only uses absolute `M`, `Q` and `Z` commands.
+fn coordinates(d: &str) -> Vec<(f32, f32)> {
+    let numbers = d
+        .split_whitespace()
+        .map(|n| n.trim_start_matches(['M', 'Q']))
+        .filter(|n| *n != "Z")
+        .map(|n| n.parse::<f32>().unwrap())
+        .collect::<Vec<_>>();
+    numbers.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+}
+
+#[test]
+fn paths_follow_draw_order() {
+    let scene = scene();
+    let (square, circle) = (Square::new(1.), Arc::circle(0.5));
+    square.color(RED).stroke(0.05, BLUE);
+    circle.color(BLUE).fill(false).stroke(0.1, RED);
+    scene.add(&square);
+    scene.add(&circle);
+
+    let svg = scene.to_svg();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let root = document.root_element();
+    assert_eq!(root.attribute("viewBox"), Some("0 0 128 128"));
+    let paths = root
+        .descendants()
+        .filter(|n| n.has_tag_name("path"))
+        .collect::<Vec<_>>();
+    assert_eq!(paths.len(), 2);
+
+    let d = paths[0].attribute("d").unwrap();
+    assert!(d.starts_with('M') && d.contains(" Q") && d.ends_with(" Z"));
+    assert_eq!(paths[0].attribute("fill-rule"), Some("evenodd"));
+    assert_eq!(paths[1].attribute("fill"), Some("none"));
+    assert_eq!(paths[1].attribute("stroke-linecap"), Some("butt"));
+    assert!(
+        paths[1]
+            .attribute("stroke-width")
+            .unwrap()
+            .parse::<f32>()
+            .unwrap()
+            > 0.
+    );
+}
+
+#[test]
+fn fill_rule_is_kept() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.fill_rule(FillRule::NonZero);
+    scene.add(&square);
+
+    let svg = scene.to_svg();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let path = document
+        .descendants()
+        .find(|n| n.has_tag_name("path"))
+        .unwrap();
+    assert_eq!(path.attribute("fill-rule"), Some("nonzero"));
+}
+
+#[test]
+fn matches_rendered_bounds() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.scale(1.5).shift((0.3, -0.2, 0.));
+    scene.add(&square);
+
+    let svg = scene.to_svg();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let path = document
+        .descendants()
+        .find(|n| n.has_tag_name("path"))
+        .unwrap();
+    let points = coordinates(path.attribute("d").unwrap());
+    let (min_x, max_x) = points.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+        (lo.min(p.0), hi.max(p.0))
+    });
+    let (min_y, max_y) = points.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+        (lo.min(p.1), hi.max(p.1))
+    });
+
+    let image = scene.capture();
+    let covered = image
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p.0[3] > 0)
+        .map(|(x, y, _)| (x as f32, y as f32))
+        .collect::<Vec<_>>();
+    let rendered_x = covered.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+        (lo.min(p.0), hi.max(p.0 + 1.))
+    });
+    let rendered_y = covered.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+        (lo.min(p.1), hi.max(p.1 + 1.))
+    });
+    for (svg, gpu) in [
+        (min_x, rendered_x.0),
+        (max_x, rendered_x.1),
+        (min_y, rendered_y.0),
+        (max_y, rendered_y.1),
+    ] {
+        assert!(
+            (svg - gpu).abs() <= 1.,
+            "SVG edge {svg}, rendered edge {gpu}"
+        );
+    }
+}
+
+#[test]
+fn gradients_become_definitions() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.gradient(
+        Gradient::linear((-0.5, 0.), (0.5, 0.))
+            .stop(0., RED)
+            .stop(1., BLUE),
+    );
+    scene.add(&square);
+
+    let svg = scene.to_svg();
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let gradient = document
+        .descendants()
+        .find(|n| n.has_tag_name("linearGradient"))
+        .unwrap();
+    let id = gradient.attribute("id").unwrap();
+    let path = document
+        .descendants()
+        .find(|n| n.has_tag_name("path"))
+        .unwrap();
+    assert_eq!(path.attribute("fill"), Some(format!("url(#{id})").as_str()));
+    // Stops are padded to the maximum count
+    assert_eq!(gradient.children().filter(|n| n.is_element()).count(), 4);
+}
+
+#[test]
+fn background_is_a_rect_below_the_paths() {
+    let scene = scene();
+    scene.add(&Square::new(1.));
+    assert!(!scene.to_svg().contains("<rect"));
+    scene.set_background((0., 0., 1., 1.));
+    let svg = scene.to_svg();
+    let rect = svg
+        .find(r##"<rect width="100%" height="100%" fill="#0000ff"/>"##)
+        .unwrap();
+    assert!(rect < svg.find("<path").unwrap());
+}
+
+#[test]
+fn blend_modes_and_straight_colors() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square
+        .color((0.5, 0., 0., 0.5))
+        .premultiplied(true)
+        .blend_mode(BlendMode::Screen);
+    scene.add(&square);
+    let svg = scene.to_svg();
+    assert!(
+        svg.contains(r##"fill="#ff0000" fill-opacity="0.5""##),
+        "{svg}"
+    );
+    assert!(svg.contains(r#"style="mix-blend-mode:screen""#), "{svg}");
+}
+
+#[test]
+fn opacity_is_multiplied_in() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square
+        .color((1., 0., 0., 0.5))
+        .stroke(0.1, (0., 0., 1., 1.))
+        .fill_opacity(0.5)
+        .stroke_opacity(0.25);
+    scene.add(&square);
+    let svg = scene.to_svg();
+    assert!(svg.contains(r#"fill-opacity="0.25""#), "{svg}");
+    assert!(svg.contains(r#"stroke-opacity="0.25""#), "{svg}");
+}
+
+#[test]
+fn imported_colors_round_trip() {
+    let scene = scene();
+    let svg = SvgMobject::from_svg(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+            <rect width="10" height="10" fill="#223344" stroke="#a0c0e0"/>
+        </svg>"##,
+        1.,
+    )
+    .unwrap();
+    scene.add(&svg[0]);
+    let svg = scene.to_svg();
+    assert!(svg.contains(r##"fill="#223344""##), "{svg}");
+    assert!(svg.contains(r##"stroke="#a0c0e0""##), "{svg}");
+}
@@ -0,0 +1,480 @@
+//! Golden-image tests for the QBezier renderer.
+//!
+//! Scenes are rendered offscreen and compared against the PNGs in `tests/golden`. Run with
+//! `UPDATE_GOLDEN=1` to regenerate the references, and pick a software adapter in CI with
+//! e.g. `WGPU_ADAPTER_NAME=llvmpipe`. On failure the actual image and a diff are written to
+//! `target/golden`.
+
+use std::path::{Path, PathBuf};
+
+use cgmath::{Deg, Quaternion, Rotation3};
+use image::{Rgba, RgbaImage};
+use webg::animations::{Animatable, FadeTransform, Timed, Transformation};
+use webg::core::color::{BLUE, RED, YELLOW};
+use webg::core::{BlendMode, Context, DepthMode, Gradient, LineCap, LineJoin, Scene};
+use webg::geometry::shapes::{Arc, Image, Square, Triangle};
+use webg::geometry::svg::SvgMobject;
+use webg::geometry::tex::MathTex;
+use webg::geometry::text::{Font, Text};
+
+const SIZE: (u32, u32) = (128, 128);
+/// Largest allowed difference of a single channel before a pixel counts as mismatched.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Fraction of mismatched pixels allowed, to absorb rasterization differences between drivers.
+const MISMATCH_TOLERANCE: f32 = 0.002;
+
+fn scene() -> Scene<'static> {
+    let ctx = pollster::block_on(Context::init()).attach_texture(SIZE.0, SIZE.1);
+    Scene::new(ctx)
+}
+
+fn msaa_scene() -> Scene<'static> {
+    let ctx = pollster::block_on(Context::init()).attach_texture(SIZE.0, SIZE.1);
+    Scene::with_msaa(ctx, 4)
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target/golden")
+}
+
+fn diff_image(actual: &RgbaImage, expected: &RgbaImage) -> (RgbaImage, usize) {
+    let mut mismatched = 0;
+    let diff = RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let (a, e) = (actual.get_pixel(x, y), expected.get_pixel(x, y));
+        let delta =
+            a.0.iter()
+                .zip(e.0)
+                .map(|(a, e)| a.abs_diff(e))
+                .max()
+                .unwrap();
+        if delta > CHANNEL_TOLERANCE {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let luma = (a.0[0] as u16 + a.0[1] as u16 + a.0[2] as u16) / 3;
+            let luma = (luma * a.0[3] as u16 / 255 / 4) as u8;
+            Rgba([luma, luma, luma, 255])
+        }
+    });
+    (diff, mismatched)
+}
+
+fn assert_golden(scene: &Scene<'_>, name: &str) {
+    let actual = scene.capture();
+    let path = golden_dir().join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(golden_dir()).unwrap();
+        actual.save(&path).unwrap();
+        return;
+    }
+
+    let expected = image::open(&path)
+        .unwrap_or_else(|e| panic!("Missing reference {path:?} ({e}), run with UPDATE_GOLDEN=1"))
+        .to_rgba8();
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "{name}: size differs from reference"
+    );
+
+    let (diff, mismatched) = diff_image(&actual, &expected);
+    let total = (actual.width() * actual.height()) as usize;
+    if mismatched as f32 > total as f32 * MISMATCH_TOLERANCE {
+        let dir = output_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        actual.save(dir.join(format!("{name}.actual.png"))).unwrap();
+        diff.save(dir.join(format!("{name}.diff.png"))).unwrap();
+        panic!("{name}: {mismatched}/{total} pixels differ from reference, see {dir:?}");
+    }
+}
+
+#[test]
+fn square() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.color((0.8, 0.95, 0.05, 1.0));
+    scene.add(&square);
+    assert_golden(&scene, "square");
+}
+
+#[test]
+fn triangle() {
+    let scene = scene();
+    let triangle = Triangle::new(1.);
+    triangle.color((0.8, 0.05, 0.05, 1.0));
+    scene.add(&triangle);
+    assert_golden(&scene, "triangle");
+}
+
+#[test]
+fn circle() {
+    let scene = scene();
+    let circle = Arc::circle(1.);
+    circle.scale(0.5).color((0.1, 0.4, 0.9, 1.0));
+    scene.add(&circle);
+    assert_golden(&scene, "circle");
+}
+
+#[test]
+fn transformed() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.shift((0.5, 0.0, 0.0)).color((0.8, 0.95, 0.05, 1.0));
+    scene.add(&square);
+    assert_golden(&scene, "transformed");
+}
+
+#[test]
+fn animate_builder() {
+    let scene = scene();
+    let square = Square::new(1.);
+    scene.add(&square);
+
+    let mut anim = square
+        .animate()
+        .shift((0.5, 0.0, 0.0))
+        .color((0.8, 0.95, 0.05, 1.0));
+    anim.begin();
+    anim.apply(anim.duration());
+    assert_golden(&scene, "transformed");
+}
+
+#[test]
+fn mid_transformation() {
+    let scene = scene();
+    let circle = Arc::circle(1.);
+    circle.scale(0.5).color((0.1, 0.4, 0.9, 1.0));
+    let triangle = Triangle::new(1.);
+    triangle.color((0.8, 0.05, 0.05, 1.0));
+    scene.add(&circle);
+
+    let mut anim = Transformation::new(&circle, &triangle, 1.);
+    anim.begin();
+    anim.apply(0.5);
+    assert_golden(&scene, "circle_to_triangle");
+}
+
+#[test]
+fn stroked_square() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square
+        .fill(false)
+        .stroke(0.1, (0.9, 0.9, 0.9, 1.0))
+        .rotate(Quaternion::from_angle_z(Deg(30.)));
+    scene.add(&square);
+    assert_golden(&scene, "stroked_square");
+}
+
+#[test]
+fn filled_and_stroked_triangle() {
+    let scene = scene();
+    let triangle = Triangle::new(1.);
+    triangle
+        .color((0.8, 0.05, 0.05, 1.0))
+        .stroke(0.08, (0.1, 0.4, 0.9, 0.5))
+        .stroke_style(LineCap::Butt, LineJoin::Round);
+    scene.add(&triangle);
+    assert_golden(&scene, "filled_and_stroked_triangle");
+}
+
+#[test]
+fn open_arc_caps() {
+    let scene = scene();
+    let arc = Arc::new(0.6, std::f32::consts::PI);
+    arc.fill(false)
+        .stroke(0.15, (0.8, 0.95, 0.05, 1.0))
+        .stroke_style(LineCap::Round, LineJoin::Bevel);
+    scene.add(&arc);
+    assert_golden(&scene, "open_arc_caps");
+}
+
+#[test]
+fn circle_msaa() {
+    let scene = msaa_scene();
+    let circle = Arc::circle(1.);
+    circle.scale(0.5).color((0.1, 0.4, 0.9, 1.0));
+    circle.stroke(0.05, (0.9, 0.9, 0.9, 1.0));
+    scene.add(&circle);
+    assert_golden(&scene, "circle_msaa");
+}
+
+#[test]
+fn linear_gradient() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.gradient(
+        Gradient::linear((-0.5, -0.5), (0.5, 0.5))
+            .stop(0., RED)
+            .stop(0.5, YELLOW)
+            .stop(1., BLUE),
+    );
+    scene.add(&square);
+    assert_golden(&scene, "linear_gradient");
+}
+
+#[test]
+fn radial_gradient() {
+    let scene = scene();
+    let circle = Arc::circle(0.5);
+    circle.gradient(
+        Gradient::radial((0., 0.), 0.5)
+            .stop(0., YELLOW)
+            .stop(1., RED),
+    );
+    scene.add(&circle);
+    assert_golden(&scene, "radial_gradient");
+}
+
+#[test]
+fn solid_to_gradient() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.color(BLUE);
+    scene.add(&square);
+
+    let mut anim = square
+        .animate()
+        .gradient(
+            Gradient::linear((-0.5, 0.), (0.5, 0.))
+                .stop(0., RED)
+                .stop(1., YELLOW),
+        )
+        .with_easing(webg::animations::easing::Linear);
+    anim.begin();
+    anim.apply(0.5);
+    assert_golden(&scene, "solid_to_gradient");
+}
+
+/// 4x2 image with a distinct color per pixel, to catch flipped or mirrored UVs.
+fn checker() -> image::DynamicImage {
+    let colors = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 0, 255],
+        [0, 255, 255, 255],
+        [255, 0, 255, 255],
+        [255, 255, 255, 255],
+        [40, 40, 40, 255],
+    ];
+    RgbaImage::from_fn(4, 2, |x, y| Rgba(colors[(y * 4 + x) as usize])).into()
+}
+
+#[test]
+fn image_mobject() {
+    let scene = scene();
+    let image = Image::new(checker(), 0.8);
+    image.rotate(Quaternion::from_angle_z(Deg(15.)));
+    scene.add(&image);
+    assert_golden(&scene, "image_mobject");
+}
+
+#[test]
+fn textured_circle() {
+    let scene = scene();
+    let circle = Arc::circle(0.5);
+    circle.texture(checker()).color((1.0, 1.0, 1.0, 0.5));
+    scene.add(&circle);
+    assert_golden(&scene, "textured_circle");
+}
+
+/// Cantarell (SIL Open Font License), small enough to keep in the repository.
+fn font() -> Font {
+    Font::from_file(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts/Cantarell-Regular.ttf"))
+        .unwrap()
+}
+
+#[test]
+fn text() {
+    let scene = scene();
+    let text = Text::new("Ag%\nWAVE", &font(), 0.6);
+    text.color((0.1, 0.1, 0.5, 1.0));
+    scene.add(&text);
+    assert_golden(&scene, "text");
+}
+
+#[test]
+fn text_to_square() {
+    let scene = scene();
+    let text = Text::new("o", &font(), 1.5);
+    text.color((0.9, 0.9, 0.9, 1.0));
+    let square = Square::new(1.);
+    square.color((0.8, 0.95, 0.05, 1.0));
+    scene.add(&text);
+
+    let mut anim = Transformation::new(&text, &square, 1.);
+    anim.begin();
+    anim.apply(0.3);
+    assert_golden(&scene, "text_to_square");
+}
+
+#[test]
+fn text_to_square_end() {
+    let scene = scene();
+    let text = Text::new("o", &font(), 1.5);
+    let square = Square::new(1.);
+    square.color((0.8, 0.95, 0.05, 1.0));
+    scene.add(&text);
+
+    // Ends exactly on the square, the hole of the glyph leaves no wedge behind
+    let mut anim = Transformation::new(&text, &square, 1.);
+    anim.begin();
+    anim.apply(1.);
+    assert_eq!(text.borrow().points.subpaths().count(), 1);
+    assert_golden(&scene, "square");
+}
+
+#[test]
+fn math_tex() {
+    let scene = scene();
+    let tex = MathTex::from_svg(include_str!("svg/fraction.svg"), 1.2).unwrap();
+    for glyph in &tex {
+        glyph.color((0.9, 0.9, 0.9, 1.0));
+        scene.add(glyph);
+    }
+    tex[4].color(YELLOW).shift((0., 0.1, 0.));
+    assert_golden(&scene, "math_tex");
+}
+
+#[test]
+fn svg_mobject() {
+    let scene = scene();
+    let svg = SvgMobject::from_file(golden_dir().join("../svg/shapes.svg"), 1.8).unwrap();
+    for path in &svg {
+        scene.add(path);
+    }
+    assert_golden(&scene, "svg_mobject");
+}
+
+#[test]
+fn fill_rules() {
+    let scene = scene();
+    // The same self-intersecting star, with a hole in the middle only under even-odd
+    let svg = SvgMobject::from_svg(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 100">
+            <polygon points="50,5 79,90 5,35 95,35 21,90" fill="#e04040" fill-rule="evenodd"/>
+            <polygon points="150,5 179,90 105,35 195,35 121,90" fill="#40a0e0"/>
+        </svg>"##,
+        0.9,
+    )
+    .unwrap();
+    for path in &svg {
+        scene.add(path);
+    }
+    assert_golden(&scene, "fill_rules");
+}
+
+#[test]
+fn layers() {
+    let scene = scene();
+    let (front, back) = (Square::new(1.), Square::new(1.));
+    front.z_index(1).color(RED);
+    back.shift((0.4, 0.4, 0.)).color(BLUE);
+    scene.add(&front);
+    scene.add(&back);
+    assert_golden(&scene, "layers");
+}
+
+#[test]
+fn depth_tested() {
+    let scene = scene();
+    scene.set_depth_mode(DepthMode::Tested);
+    let (near, far) = (Square::new(1.), Square::new(1.));
+    near.color(RED).stroke(0.05, YELLOW);
+    far.shift((0.4, 0.4, 0.5)).color(BLUE).stroke(0.05, YELLOW);
+    // Tilted through the near square, so each hides part of the other
+    let tilted = Square::new(1.2);
+    tilted
+        .rotate(Quaternion::from_angle_y(Deg(60.)))
+        .shift((-0.3, -0.3, 0.))
+        .color((0.1, 0.8, 0.1, 1.));
+    scene.add(&near);
+    scene.add(&far);
+    scene.add(&tilted);
+    assert_golden(&scene, "depth_tested");
+}
+
+#[test]
+fn color_background() {
+    let scene = msaa_scene();
+    scene.set_background((0.05, 0.05, 0.2, 1.));
+    let circle = Arc::circle(0.5);
+    circle.color((1.0, 1.0, 1.0, 0.5));
+    scene.add(&circle);
+    assert_golden(&scene, "color_background");
+}
+
+#[test]
+fn image_background() {
+    let scene = scene();
+    scene.set_background(checker());
+    let square = Square::new(0.8);
+    square.color((0.0, 0.0, 0.0, 0.5));
+    scene.add(&square);
+    assert_golden(&scene, "image_background");
+}
+
+#[test]
+fn blend_modes() {
+    let scene = msaa_scene();
+    scene.set_background((0.1, 0.1, 0.1, 1.));
+    let stripe = Square::new(2.);
+    stripe.scale_vec((1., 0.3, 1.)).color((0.2, 0.4, 0.8, 1.));
+    scene.add(&stripe);
+    let modes = [
+        BlendMode::Normal,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+    ];
+    for (i, mode) in modes.into_iter().enumerate() {
+        let circle = Arc::circle(0.22);
+        circle.color((0.9, 0.6, 0.1, 0.8)).blend_mode(mode).shift((
+            -0.75 + 0.5 * i as f32,
+            0.2,
+            0.,
+        ));
+        scene.add(&circle);
+    }
+    assert_golden(&scene, "blend_modes");
+}
+
+#[test]
+fn opacity() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square
+        .color((0.8, 0.2, 0.2, 1.0))
+        .stroke(0.08, (1.0, 1.0, 1.0, 1.0))
+        .fill_opacity(0.4);
+    let circle = Arc::circle(0.3);
+    circle
+        .color((0.2, 0.3, 0.9, 1.0))
+        .shift((0.4, 0.3, 0.))
+        .opacity(0.6);
+    scene.add(&square);
+    scene.add(&circle);
+    assert_golden(&scene, "opacity");
+}
+
+#[test]
+fn mid_fade_transform() {
+    let scene = scene();
+    let square = Square::new(1.);
+    square.color((0.1, 0.4, 0.9, 1.0)).shift((-0.4, 0., 0.));
+    let triangle = Triangle::new(1.);
+    triangle.color((0.8, 0.05, 0.05, 1.0)).shift((0.4, 0., 0.));
+    scene.add(&square);
+    scene.add(&triangle);
+
+    let mut anim = FadeTransform::new(&square, &triangle, 1.);
+    anim.begin();
+    anim.apply(0.5);
+    assert_golden(&scene, "mid_fade_transform");
+}
@@ -0,0 +1,70 @@
+use cgmath::{InnerSpace, Vector3};
+use webg::core::Path;
+use webg::geometry::bezier;
+
+fn square(offset: f32, size: f32) -> Vec<Vector3<f32>> {
+    let mut points = vec![Vector3::new(offset, offset, 0.)];
+    for (x, y) in [(1., 0.), (1., 1.), (0., 1.)] {
+        bezier::line_to(
+            &mut points,
+            Vector3::new(offset + x * size, offset + y * size, 0.),
+        );
+    }
+    bezier::close(&mut points);
+    points
+}
+
+fn quadratic(p: &[Vector3<f32>], t: f32) -> Vector3<f32> {
+    let s = 1. - t;
+    p[0] * (s * s) + p[1] * (2. * s * t) + p[2] * (t * t)
+}
+
+#[test]
+fn subpaths_are_linked_by_moves() {
+    let (outer, inner) = (square(0., 3.), square(1., 1.));
+    let path = Path::from_subpaths([outer.clone(), vec![Vector3::new(5., 5., 0.)], inner.clone()]);
+    // Outer square, move, inner square; the single point has no segments and is dropped
+    assert_eq!(path.len(), 9 + 2 + 8);
+    assert_eq!(path.segments(), 9);
+    assert!(path.is_move(4));
+    assert_eq!(
+        path.subpaths().collect::<Vec<_>>(),
+        [&outer[..], &inner[..]]
+    );
+}
+
+#[test]
+fn resample_keeps_the_curve() {
+    let arc = vec![
+        Vector3::new(1., 0., 0.),
+        Vector3::new(1., 1., 0.),
+        Vector3::new(0., 1., 0.),
+    ];
+    let path = Path::from_subpaths([arc.clone(), square(2., 1.)]);
+    let resampled = path.resample(24);
+    assert_eq!(resampled.segments(), 24);
+    assert_eq!(resampled.subpaths().count(), 2);
+
+    let pieces = resampled.subpaths().next().unwrap();
+    let n = pieces.len() / 2;
+    assert!(n > 1);
+    for (i, piece) in pieces.windows(3).step_by(2).enumerate() {
+        for s in [0., 0.3, 0.5, 1.] {
+            let t = (i as f32 + s) / n as f32;
+            let distance = (quadratic(piece, s) - quadratic(&arc, t)).magnitude();
+            assert!(distance < 1e-5, "{distance}");
+        }
+    }
+}
+
+#[test]
+fn lerp_ends_on_either_path() {
+    let one = Path::from_subpaths([square(0., 1.), square(2., 1.)]);
+    let other = Path::from(square(0., 2.)).resample(one.segments());
+    assert_eq!(one.lerp(&other, 0.), one);
+    assert_eq!(one.lerp(&other, 1.), other);
+    // Subpaths switch halfway
+    assert_eq!(one.lerp(&other, 0.4).subpaths().count(), 2);
+    assert_eq!(one.lerp(&other, 0.6).subpaths().count(), 1);
+    assert_eq!(one.lerp(&other, 0.6).len(), one.len());
+}
@@ -0,0 +1,194 @@
+use std::rc::Rc;
+
+use webg::animations::Animatable;
+use webg::core::{Context, DepthMode, Scene};
+use webg::geometry::shapes::{Arc, Square, Triangle};
+
+fn scene() -> Scene<'static> {
+    let ctx = pollster::block_on(Context::init()).attach_texture(64, 64);
+    Scene::new(ctx)
+}
+
+#[test]
+fn add_is_idempotent() {
+    let scene = scene();
+    let square = Square::new(1.);
+    let id = scene.add(&square);
+    assert_eq!(scene.add(&square), id);
+    assert_eq!(scene.borrow().object_ids(), [id]);
+
+    // A deep copy is a different object
+    let copy = square.clone();
+    assert_ne!(scene.add(&copy), id);
+    assert_eq!(scene.borrow().object_ids().len(), 2);
+}
+
+#[test]
+fn remove_keeps_other_objects_in_order() {
+    let scene = scene();
+    let (square, triangle, circle) = (Square::new(1.), Triangle::new(1.), Arc::circle(1.));
+    let a = scene.add(&square);
+    let b = scene.add(&triangle);
+    let c = scene.add(&circle);
+
+    assert!(scene.remove(&triangle));
+    assert_eq!(scene.borrow().object_ids(), [a, c]);
+    assert_eq!(scene.id_of(&triangle), None);
+    assert!(!scene.remove(&triangle));
+
+    // Re-adding gives a fresh id at the end of the draw order
+    let d = scene.add(&triangle);
+    assert_ne!(d, b);
+    assert_eq!(scene.borrow().object_ids(), [a, c, d]);
+
+    assert!(scene.remove_by_id(a));
+    assert!(!scene.remove_by_id(a));
+    scene.borrow_mut().render().unwrap();
+    assert_eq!(scene.borrow().object_ids(), [c, d]);
+}
+
+#[test]
+fn changes_between_frames_match_a_fresh_scene() {
+    let (square, triangle, circle) = (Square::new(1.), Triangle::new(1.), Arc::circle(0.5));
+    square
+        .color((0.8, 0.2, 0.2, 0.7))
+        .stroke(0.05, (1., 1., 1., 1.));
+    triangle.color((0.2, 0.8, 0.2, 0.7));
+    circle
+        .color((0.2, 0.2, 0.8, 0.7))
+        .stroke(0.1, (1., 1., 0., 1.));
+
+    let scene = scene();
+    scene.add(&square);
+    scene.add(&triangle);
+    scene.add(&circle);
+    scene.capture();
+
+    // Same sizes, so only the changed objects are uploaded again
+    for p in triangle.borrow_mut().points.iter_mut() {
+        p.x += 0.3;
+    }
+    circle.stroke(0.2, (1., 1., 0., 1.));
+    let fresh = self::scene();
+    fresh.add(&square.clone());
+    fresh.add(&triangle.clone());
+    fresh.add(&circle.clone());
+    assert_eq!(scene.capture(), fresh.capture());
+
+    scene.remove(&triangle);
+    let fresh = self::scene();
+    fresh.add(&square.clone());
+    fresh.add(&circle.clone());
+    assert_eq!(scene.capture(), fresh.capture());
+}
+
+#[test]
+fn layers_and_reordering() {
+    let scene = scene();
+    let (square, triangle, circle) = (Square::new(1.), Triangle::new(1.), Arc::circle(1.));
+    let a = scene.add(&square);
+    let b = scene.add(&triangle);
+    let c = scene.add(&circle);
+
+    square.z_index(1);
+    assert_eq!(scene.borrow().object_ids(), [b, c, a]);
+    assert!(scene.send_to_back(&circle));
+    assert_eq!(scene.borrow().object_ids(), [c, b, a]);
+    // Only within its layer
+    assert!(scene.bring_to_front(&circle));
+    assert_eq!(scene.borrow().object_ids(), [b, c, a]);
+    assert!(!scene.bring_to_front(&Square::new(1.)));
+
+    // Farthest first when depth is tested, the camera looks along +z
+    scene.set_depth_mode(DepthMode::Tested);
+    triangle.shift((0., 0., 1.));
+    assert_eq!(scene.borrow().object_ids(), [b, c, a]);
+    circle.shift((0., 0., 2.));
+    assert_eq!(scene.borrow().object_ids(), [c, b, a]);
+}
+
+#[test]
+fn background_is_cleared_every_frame() {
+    let scene = scene();
+    // Transparent by default, so exported frames keep their alpha
+    assert_eq!(scene.capture().get_pixel(0, 0).0, [0, 0, 0, 0]);
+
+    scene.set_background((1., 0., 0., 1.));
+    let square = Square::new(1.);
+    scene.add(&square);
+    scene.capture();
+    scene.remove(&square);
+    let frame = scene.capture();
+    assert_eq!(frame.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(frame.get_pixel(32, 32).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn background_transition_reaches_its_color() {
+    let scene = scene();
+    scene.set_background((1., 0., 0., 1.));
+    let mut transition = scene.animate_background((0., 0., 1., 1.), 1.);
+    transition.begin();
+    assert!(transition.apply(0.5));
+    let middle = scene.capture().get_pixel(0, 0).0;
+    assert!(middle[0] > 0 && middle[2] > 0, "{middle:?}");
+    transition.apply(1.);
+    assert_eq!(scene.capture().get_pixel(0, 0).0, [0, 0, 255, 255]);
+}
+
+#[test]
+fn premultiplied_colors_blend_like_straight_ones() {
+    let straight = scene();
+    let square = Square::new(1.);
+    square
+        .color((0.8, 0.4, 0.2, 0.5))
+        .stroke(0.1, (0.2, 0.2, 1., 0.5));
+    straight.add(&square);
+
+    let premultiplied = scene();
+    let square = Square::new(1.);
+    square
+        .color((0.4, 0.2, 0.1, 0.5))
+        .stroke(0.1, (0.1, 0.1, 0.5, 0.5))
+        .premultiplied(true);
+    premultiplied.add(&square);
+
+    let (a, b) = (straight.capture(), premultiplied.capture());
+    for (a, b) in a.pixels().zip(b.pixels()) {
+        assert!(
+            a.0.iter().zip(b.0).all(|(a, b)| a.abs_diff(b) <= 1),
+            "{a:?} {b:?}"
+        );
+    }
+}
+
+#[test]
+fn replacing_an_object_with_a_same_sized_one() {
+    let scene = scene();
+    let triangle = Triangle::new(1.);
+    scene.add(&triangle);
+    let square = Square::new(1.);
+    square.stroke(0.1, (1., 0., 0., 1.));
+    scene.add(&square);
+    scene.capture();
+
+    // Same sizes and revision as the removed square, and usually its freed allocation too
+    let address = Rc::as_ptr(&square) as usize;
+    scene.remove(&square);
+    drop(square);
+    let mut candidates = Vec::new();
+    let smaller = loop {
+        let candidate = Square::new(0.5);
+        if Rc::as_ptr(&candidate) as usize == address || candidates.len() == 100 {
+            break candidate;
+        }
+        candidates.push(candidate);
+    };
+    smaller.stroke(0.1, (1., 0., 0., 1.));
+    scene.add(&smaller);
+
+    let fresh = self::scene();
+    fresh.add(&triangle.clone());
+    fresh.add(&smaller.clone());
+    assert_eq!(scene.capture(), fresh.capture());
+}
@@ -0,0 +1,115 @@
+use cgmath::{InnerSpace, Vector3};
+use webg::core::{tessellate, LineCap, LineJoin, Path, Stroke, StrokeMesh};
+use webg::geometry::bezier;
+
+const WIDTH: f32 = 0.2;
+
+fn polyline(points: &[(f32, f32)]) -> Path {
+    let mut path = vec![Vector3::new(points[0].0, points[0].1, 0.)];
+    for &(x, y) in &points[1..] {
+        bezier::line_to(&mut path, Vector3::new(x, y, 0.));
+    }
+    Path::from(path)
+}
+
+fn stroke(cap: LineCap, join: LineJoin) -> Stroke {
+    Stroke {
+        width: WIDTH,
+        cap,
+        join,
+        ..Default::default()
+    }
+}
+
+fn positions(mesh: &StrokeMesh) -> Vec<Vector3<f32>> {
+    mesh.indices
+        .iter()
+        .map(|&i| mesh.vertices[i as usize].position())
+        .collect()
+}
+
+/// Farthest a vertex around `p` gets from it, ignoring the other ends of the edges.
+fn reach(mesh: &StrokeMesh, p: (f32, f32)) -> f32 {
+    let p = Vector3::new(p.0, p.1, 0.);
+    positions(mesh)
+        .iter()
+        .map(|v| (v - p).magnitude())
+        .filter(|&d| d < 0.5)
+        .fold(0., f32::max)
+}
+
+#[test]
+fn zero_width_has_no_triangles() {
+    let line = polyline(&[(0., 0.), (1., 0.)]);
+    let mesh = tessellate(&line, &Stroke::default());
+    assert!(mesh.vertices.is_empty() && mesh.indices.is_empty());
+}
+
+#[test]
+fn caps_extend_the_ends() {
+    let line = polyline(&[(0., 0.), (1., 0.)]);
+    let extent = |cap| {
+        let points = positions(&tessellate(&line, &stroke(cap, LineJoin::Miter)));
+        assert_eq!(points.len() % 3, 0);
+        let xs = points.iter().map(|p| p.x);
+        let ys = points.iter().map(|p| p.y.abs());
+        (
+            xs.clone().fold(f32::INFINITY, f32::min),
+            xs.fold(f32::NEG_INFINITY, f32::max),
+            ys.fold(0., f32::max),
+        )
+    };
+    let half = WIDTH / 2.;
+    assert_eq!(extent(LineCap::Butt), (0., 1., half));
+    assert_eq!(extent(LineCap::Square), (-half, 1. + half, half));
+    let (min, max, y) = extent(LineCap::Round);
+    assert!((min + half).abs() < 1e-5 && (max - 1. - half).abs() < 1e-5);
+    assert!((y - half).abs() < 1e-5);
+    // Round caps stay within the half width of the ends
+    let round = tessellate(&line, &stroke(LineCap::Round, LineJoin::Miter));
+    assert!((reach(&round, (1., 0.)) - half).abs() < 1e-5);
+}
+
+#[test]
+fn joins_fill_the_outer_corner() {
+    let corner = polyline(&[(0., 0.), (1., 0.), (1., 1.)]);
+    let half = WIDTH / 2.;
+    let miter = tessellate(&corner, &stroke(LineCap::Butt, LineJoin::Miter));
+    let bevel = tessellate(&corner, &stroke(LineCap::Butt, LineJoin::Bevel));
+    let round = tessellate(&corner, &stroke(LineCap::Butt, LineJoin::Round));
+
+    // The miter tip is the outer corner of the two edges
+    assert!((reach(&miter, (1., 0.)) - half * 2f32.sqrt()).abs() < 1e-5);
+    assert!(positions(&miter)
+        .iter()
+        .any(|p| (p - Vector3::new(1. + half, -half, 0.)).magnitude() < 1e-5));
+    assert!((reach(&bevel, (1., 0.)) - half).abs() < 1e-5);
+    assert!((reach(&round, (1., 0.)) - half).abs() < 1e-5);
+    assert!(round.vertices.len() > bevel.vertices.len());
+}
+
+#[test]
+fn sharp_miters_fall_back_to_bevels() {
+    // A turn of about 150 degrees, whose miter is just over the default limit of 4
+    let spike = polyline(&[(0., 0.), (1., 0.), (0., 0.5)]);
+    let mesh = tessellate(&spike, &stroke(LineCap::Butt, LineJoin::Miter));
+    assert!(reach(&mesh, (1., 0.)) <= WIDTH / 2. + 1e-5);
+
+    let long = Stroke {
+        miter_limit: 100.,
+        ..stroke(LineCap::Butt, LineJoin::Miter)
+    };
+    assert!(reach(&tessellate(&spike, &long), (1., 0.)) > WIDTH * 2.);
+}
+
+#[test]
+fn closed_subpaths_have_no_caps() {
+    let square = polyline(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)]);
+    let mesh = tessellate(&square, &stroke(LineCap::Square, LineJoin::Bevel));
+    let half = WIDTH / 2.;
+    // Square caps at the start would reach past the corner along the first edge
+    assert!(reach(&mesh, (0., 0.)) <= half * 2f32.sqrt() + 1e-5);
+    assert!(positions(&mesh)
+        .iter()
+        .all(|p| p.x >= -half - 1e-5 && p.y >= -half - 1e-5));
+}
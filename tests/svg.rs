@@ -0,0 +1,106 @@
+use cgmath::{InnerSpace, Vector3};
+use webg::core::FillRule;
+use webg::geometry::svg::{self, Affine, SvgMobject};
+
+const SHAPES: &str = include_str!("svg/shapes.svg");
+
+/// A channel of an sRGB color as the linear value shapes are drawn with.
+fn linear(byte: u8) -> f32 {
+    ((byte as f32 / 255. + 0.055) / 1.055).powf(2.4)
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn relative_path_commands() {
+    let contours =
+        svg::parse_path_data("m10 10 h20 v20 h-20 z", Affine::IDENTITY, 0.01, false).unwrap();
+    assert_eq!(contours.len(), 1);
+    let corners = contours[0].iter().step_by(2).copied().collect::<Vec<_>>();
+    assert_eq!(
+        corners,
+        [(10., 10.), (30., 10.), (30., 30.), (10., 30.), (10., 10.)]
+            .map(|(x, y)| Vector3::new(x, y, 0.))
+    );
+}
+
+#[test]
+fn arcs_stay_on_the_ellipse() {
+    let tolerance = 0.01;
+    let contours =
+        svg::parse_path_data("M0 0 A5 5 0 0 1 10 0", Affine::IDENTITY, tolerance, false).unwrap();
+    let center = Vector3::new(5., 0., 0.);
+    let points = &contours[0];
+    assert_eq!(*points.last().unwrap(), Vector3::new(10., 0., 0.));
+    for segment in points.windows(3).step_by(2) {
+        assert_close((segment[2] - center).magnitude(), 5.);
+        let middle = (segment[0] + segment[1] * 2. + segment[2]) / 4.;
+        assert!(((middle - center).magnitude() - 5.).abs() <= tolerance);
+    }
+    // Sweeping clockwise on screen, where y points down
+    assert!(points.iter().all(|p| p.y <= 1e-3));
+}
+
+#[test]
+fn styles_are_inherited() {
+    let elements = svg::parse(SHAPES, 0.1).unwrap();
+    // The hidden rect and the unpainted circle are skipped
+    assert_eq!(elements.len(), 7);
+
+    let background = elements[0].style;
+    let fill = background.fill.unwrap();
+    assert_close(fill.x, linear(0x22));
+    assert_close(fill.z, linear(0x44));
+    assert_eq!(background.stroke, None);
+
+    let circle = elements[1].style;
+    assert_close(circle.fill.unwrap().y, linear(0xa5));
+    assert_eq!(circle.stroke, Some((1., 1., 1., 1.).into()));
+    assert_close(circle.stroke_width, 3.);
+
+    let ellipse = elements[2].style;
+    assert_close(ellipse.fill.unwrap().w, 0.5);
+
+    let polyline = elements[4].style;
+    assert_eq!(polyline.fill, None);
+    assert_eq!(polyline.stroke, Some((1., 1., 0., 1.).into()));
+}
+
+#[test]
+fn fill_rule_defaults_to_nonzero() {
+    let elements = svg::parse(
+        r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <rect width="1" height="1"/>
+            <g style="fill-rule: evenodd">
+                <rect width="1" height="1"/>
+                <rect width="1" height="1" fill-rule="nonzero"/>
+            </g>
+        </svg>"#,
+        0.1,
+    )
+    .unwrap();
+    let rules = elements
+        .iter()
+        .map(|e| e.style.fill_rule)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rules,
+        [FillRule::NonZero, FillRule::EvenOdd, FillRule::NonZero]
+    );
+}
+
+#[test]
+fn mobject_from_file() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/svg/shapes.svg");
+    let svg = SvgMobject::from_file(path, 2.).unwrap();
+    assert_eq!(svg.len(), 7);
+    assert!(matches!(
+        SvgMobject::from_file("missing.svg", 1.),
+        Err(svg::SvgError::Io(_))
+    ));
+}
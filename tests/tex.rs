@@ -0,0 +1,78 @@
+use std::process::Command;
+
+use webg::core::HasPoints;
+use webg::geometry::tex::{MathTex, TexCompiler};
+
+const FRACTION: &str = include_str!("svg/fraction.svg");
+const SYMBOLS: &str = include_str!("svg/symbols.svg");
+
+/// Whether `latex` and `dvisvgm` can be run, since most machines don't have TeX installed.
+fn has_tex() -> bool {
+    ["latex", "dvisvgm"]
+        .iter()
+        .all(|program| Command::new(program).arg("--version").output().is_ok())
+}
+
+#[test]
+fn glyphs_are_separate_mobjects() {
+    let tex = MathTex::from_svg(FRACTION, 1.).unwrap();
+    // o, fraction bar, x, +, 1
+    assert_eq!(tex.len(), 5);
+    assert!(tex
+        .glyphs()
+        .iter()
+        .all(|glyph| glyph.borrow().calc_points().len() >= 3));
+}
+
+#[test]
+fn compile_uses_cache() {
+    let compiler = TexCompiler::new().cache_dir(env!("CARGO_TARGET_TMPDIR"));
+    let formula = r"\frac{o}{x} + 1";
+    let path = compiler.cache_path(formula);
+    std::fs::write(&path, FRACTION).unwrap();
+
+    // Served from the cache, so this passes without a TeX installation
+    assert_eq!(compiler.compile(formula).unwrap(), FRACTION);
+    let other = TexCompiler::new()
+        .cache_dir(env!("CARGO_TARGET_TMPDIR"))
+        .preamble("\\documentclass{standalone}\n");
+    assert_ne!(other.cache_path(formula), path);
+
+    let tex = MathTex::with_compiler(&compiler, formula, 1.).unwrap();
+    assert_eq!(tex.len(), 5);
+}
+
+#[test]
+fn symbols_are_drawn_where_used() {
+    let tex = MathTex::from_svg(SYMBOLS, 1.).unwrap();
+    // x, +, x. The symbols themselves are not drawn
+    assert_eq!(tex.len(), 3);
+    let (first, last) = (tex[0].borrow().calc_points(), tex[2].borrow().calc_points());
+    assert_eq!(first.len(), last.len());
+    let offset = last[0] - first[0];
+    assert!(offset.x > 0. && offset.y.abs() < 1e-5);
+    assert!(first
+        .iter()
+        .zip(last.iter())
+        .all(|(a, b)| (b - a - offset).x.abs() < 1e-5));
+}
+
+#[test]
+fn compile_runs_latex() {
+    if !has_tex() {
+        eprintln!("latex or dvisvgm not found, skipping");
+        return;
+    }
+    let compiler = TexCompiler::new();
+    let formula = r"\frac{o}{x} + 1";
+    let path = compiler.cache_path(formula);
+    assert!(path.starts_with(std::env::temp_dir().join("webg-tex")));
+    let _ = std::fs::remove_file(&path);
+
+    let svg = compiler.compile(formula).unwrap();
+    assert!(svg.contains("dvisvgm"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), svg);
+    // Same glyphs as the hand-written fixture
+    let tex = MathTex::new(formula, 1.).unwrap();
+    assert_eq!(tex.len(), MathTex::from_svg(FRACTION, 1.).unwrap().len());
+}
@@ -0,0 +1,42 @@
+use webg::core::HasPoints;
+use webg::geometry::text::{Font, Text};
+
+/// Has both `GPOS` pair adjustments and a legacy `kern` table, but isn't shipped with the repo.
+const DEJAVU: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+fn width(text: &str, font: &Font) -> f32 {
+    let points = Text::new(text, font, 1.).borrow().calc_points();
+    let (min, max) = points.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+        (min.min(p.x), max.max(p.x))
+    });
+    max - min
+}
+
+/// Renames the table `tag` in the table directory, so it is not found anymore.
+fn hide_table(mut data: Vec<u8>, tag: &[u8; 4]) -> Vec<u8> {
+    let count = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let record = (0..count)
+        .map(|i| 12 + 16 * i)
+        .find(|&record| &data[record..record + 4] == tag)
+        .expect("table is present");
+    data[record] = b'x';
+    data
+}
+
+#[test]
+fn pairs_are_kerned_from_gpos() {
+    let Ok(data) = std::fs::read(DEJAVU) else {
+        eprintln!("{DEJAVU} not found, skipping");
+        return;
+    };
+    let gpos_only = Font::from_bytes(hide_table(data.clone(), b"kern")).unwrap();
+    // Only kerning pulls the V over the foot of the A
+    let kerned = width("AV", &gpos_only);
+    assert!(kerned < width("A", &gpos_only) + width("V", &gpos_only));
+
+    // Both tables agree, and GPOS is preferred
+    let font = Font::from_bytes(data.clone()).unwrap();
+    assert_eq!(width("AV", &font), kerned);
+    let kern_only = Font::from_bytes(hide_table(data, b"GPOS")).unwrap();
+    assert_eq!(width("AV", &kern_only), kerned);
+}
@@ -0,0 +1,61 @@
+use webg::animations::easing::Linear;
+use webg::animations::{FadeIn, Timed};
+use webg::core::{Context, Scene, VideoOutput, VideoRenderer};
+use webg::geometry::shapes::Square;
+
+fn scene() -> Scene<'static> {
+    let ctx = pollster::block_on(Context::init()).attach_texture(64, 64);
+    Scene::new(ctx)
+}
+
+fn frames(dir: &std::path::Path) -> Vec<image::RgbaImage> {
+    let mut paths = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| image::open(path).unwrap().to_rgba8())
+        .collect()
+}
+
+#[test]
+fn frames_start_at_the_beginning() {
+    let scene = scene();
+    let dir = std::env::temp_dir().join("webg-video-frames");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let square = Square::new(1.);
+    let script = {
+        let scene = scene.clone();
+        async move {
+            scene.add(&square);
+            scene
+                .play(FadeIn::new(&square, 1.).with_easing(Linear))
+                .await;
+        }
+    };
+    VideoRenderer::new(&scene, VideoOutput::PngSequence(dir.clone()))
+        .fps(10)
+        .render(script)
+        .unwrap();
+
+    // One frame every 0.1s from 0 to 1s included
+    let frames = frames(&dir);
+    assert_eq!(frames.len(), 11);
+    // The square fades in from nothing, starting with the background alone
+    assert_eq!(frames[0].get_pixel(32, 32).0, [0, 0, 0, 0]);
+    assert_eq!(frames[10].get_pixel(32, 32).0[3], 255);
+}
+
+#[test]
+fn scripts_waiting_on_something_else_fail() {
+    let scene = scene();
+    let dir = std::env::temp_dir().join("webg-video-stalled");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let result = VideoRenderer::new(&scene, VideoOutput::PngSequence(dir))
+        .render(futures::future::pending());
+    assert!(result.is_err());
+}